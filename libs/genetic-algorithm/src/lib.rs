@@ -2,7 +2,10 @@
 #![allow(clippy::new_without_default, clippy::len_without_is_empty)]
 
 use rand::prelude::*;
-use std::ops::Index;
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::{ControlFlow, Index};
 
 pub trait Individual {
     fn chromosome(&self) -> &Chromosome;
@@ -29,10 +32,86 @@ pub trait MutationMethod {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
 }
 
+/// Projects a chromosome back into the feasible region, for problems where
+/// genes have to satisfy some constraints
+pub trait Repair {
+    fn repair(&self, chromosome: &mut Chromosome);
+}
+
+/// Creates a fresh random chromosome, for `GeneticAlgorithm::with_immigrants()`
+type ImmigrantFactory = Box<dyn Fn(&mut dyn RngCore) -> Chromosome + Send + Sync>;
+
 pub struct GeneticAlgorithm<S, C, M> {
     selection_method: S,
     crossover_method: C,
     mutation_method: M,
+
+    /// Number of the fittest individuals copied as-is into the next
+    /// generation
+    elitism: usize,
+
+    /// Mutation applied to elites after they're copied over; `None` keeps
+    /// them bit-identical
+    elite_mutation: Option<GaussianMutation>,
+
+    /// How much each child gets pulled toward the population's mean
+    /// chromosome after mutation:
+    /// - 0.0 = child is left as-is
+    /// - 1.0 = child becomes the mean chromosome
+    regularization: f32,
+
+    /// Whether the fittest individual is always carried over into the next
+    /// generation, even with `elitism` set to zero
+    preserve_best: bool,
+
+    /// Chromosome distance (`sigma`) within which individuals share their
+    /// fitness during selection (see `shared_fitness()`); `None` disables
+    /// fitness sharing
+    fitness_sharing: Option<f32>,
+
+    /// Rescales fitness (after fitness sharing, if enabled) before
+    /// selection; `None` leaves fitness as-is
+    sigma_scaling: Option<SigmaScaling>,
+
+    /// Further transforms fitness (after fitness sharing and sigma
+    /// scaling, if enabled) before selection; `None` leaves fitness as-is
+    fitness_pipeline: Option<FitnessPipeline>,
+
+    /// How many times `parent_b` gets re-selected when it turns out to be
+    /// the same individual as `parent_a`, so that crossover doesn't
+    /// degenerate into cloning
+    self_crossover_retries: usize,
+
+    /// Probability of an offspring being a crossover product; otherwise
+    /// it's a (mutated) clone of a single selected parent
+    crossover_chance: f32,
+
+    /// Size of the next generation (elites included); `None` keeps it the
+    /// same as the current one's
+    offspring_count: Option<usize>,
+
+    /// Whether offspring gets truncated back to the current generation's
+    /// size - i.e. a (μ, λ) strategy, together with `offspring_count`.
+    ///
+    /// Since offspring hasn't been evaluated yet, it's ranked by the mean
+    /// fitness of each child's parents.
+    truncate_offspring: bool,
+
+    /// Applied to each new chromosome after crossover, mutation and
+    /// regularization.
+    ///
+    /// Not a part of `GaConfig`, since it's not serializable.
+    repair: Option<Box<dyn Repair + Send + Sync>>,
+
+    /// Fraction of the offspring which, starting from the worst, gets
+    /// replaced by fresh random individuals (immigrants) each generation,
+    /// along with the factory creating them.
+    ///
+    /// Since offspring hasn't been evaluated yet, it's ranked by the mean
+    /// fitness of each child's parents (as with `truncate_offspring`).
+    ///
+    /// Not a part of `GaConfig`, since the factory is not serializable.
+    immigrants: Option<(f32, ImmigrantFactory)>,
 }
 impl<S, C, M> GeneticAlgorithm<S, C, M>
 where
@@ -45,449 +124,4010 @@ where
             selection_method,
             crossover_method,
             mutation_method,
+            elitism: 0,
+            elite_mutation: None,
+            regularization: 0.0,
+            preserve_best: false,
+            fitness_sharing: None,
+            sigma_scaling: None,
+            fitness_pipeline: None,
+            self_crossover_retries: 0,
+            crossover_chance: 1.0,
+            offspring_count: None,
+            truncate_offspring: false,
+            repair: None,
+            immigrants: None,
         }
     }
 
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.elitism = elitism;
+        self
+    }
+
+    pub fn with_elite_mutation(mut self, elite_mutation: GaussianMutation) -> Self {
+        self.elite_mutation = Some(elite_mutation);
+        self
+    }
+
+    pub fn with_regularization(mut self, regularization: f32) -> Self {
+        assert!((0.0..=1.0).contains(&regularization));
+
+        self.regularization = regularization;
+        self
+    }
+
+    pub fn with_preserve_best(mut self, preserve_best: bool) -> Self {
+        self.preserve_best = preserve_best;
+        self
+    }
+
+    pub fn with_fitness_sharing(mut self, sigma: f32) -> Self {
+        assert!(sigma > 0.0);
+
+        self.fitness_sharing = Some(sigma);
+        self
+    }
+
+    pub fn with_sigma_scaling(mut self, sigma_scaling: SigmaScaling) -> Self {
+        self.sigma_scaling = Some(sigma_scaling);
+        self
+    }
+
+    pub fn with_fitness_pipeline(mut self, fitness_pipeline: FitnessPipeline) -> Self {
+        self.fitness_pipeline = Some(fitness_pipeline);
+        self
+    }
+
+    pub fn with_self_crossover_retries(mut self, retries: usize) -> Self {
+        self.self_crossover_retries = retries;
+        self
+    }
+
+    pub fn with_crossover_chance(mut self, crossover_chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&crossover_chance));
+
+        self.crossover_chance = crossover_chance;
+        self
+    }
+
+    pub fn with_repair(mut self, repair: impl Repair + Send + Sync + 'static) -> Self {
+        self.repair = Some(Box::new(repair));
+        self
+    }
+
+    pub fn with_offspring_count(mut self, offspring_count: usize) -> Self {
+        self.offspring_count = Some(offspring_count);
+        self
+    }
+
+    pub fn with_truncate_offspring(mut self, truncate_offspring: bool) -> Self {
+        self.truncate_offspring = truncate_offspring;
+        self
+    }
+
+    pub fn with_immigrants(
+        mut self,
+        fraction: f32,
+        factory: impl Fn(&mut dyn RngCore) -> Chromosome + Send + Sync + 'static,
+    ) -> Self {
+        assert!((0.0..=1.0).contains(&fraction));
+
+        self.immigrants = Some((fraction, Box::new(factory)));
+        self
+    }
+
     pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> Vec<I>
     where
         I: Individual,
     {
-        assert!(!population.is_empty());
+        match self.try_evolve(rng, population) {
+            Ok(population) => population,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Like `evolve()`, but returns an error instead of panicking
+    pub fn try_evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> Result<Vec<I>, GaError>
+    where
+        I: Individual,
+    {
+        if population.is_empty() {
+            return Err(GaError::EmptyPopulation);
+        }
 
-        (0..population.len())
-            .map(|_| {
-                let parent_a = self.selection_method.select(rng, population).chromosome();
-                let parent_b = self.selection_method.select(rng, population).chromosome();
+        let elitism = if self.preserve_best {
+            self.elitism.max(1)
+        } else {
+            self.elitism
+        };
 
-                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        let elites: Vec<_> = if elitism > 0 {
+            let mut ranked: Vec<_> = population.iter().collect();
 
-                self.mutation_method.mutate(rng, &mut child);
+            ranked.sort_by(|a, b| compare_fitness_descending(*a, *b));
 
-                I::create(child)
-            })
-            .collect()
+            ranked
+                .into_iter()
+                .take(elitism)
+                .enumerate()
+                .map(|(idx, individual)| {
+                    let mut chromosome = individual.chromosome().clone();
+
+                    // With `preserve_best`, the fittest individual has to
+                    // survive verbatim
+                    let preserved = self.preserve_best && idx == 0;
+
+                    if let (Some(mutation), false) = (&self.elite_mutation, preserved) {
+                        mutation.mutate(rng, &mut chromosome);
+
+                        if let Some(repair) = &self.repair {
+                            repair.repair(&mut chromosome);
+                        }
+                    }
+
+                    I::create(chromosome)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mean = if self.regularization > 0.0 {
+            Some(population_centroid(population))
+        } else {
+            None
+        };
+
+        let effective: Option<Vec<_>> = self.effective_fitness(population).map(|fitness| {
+            fitness
+                .into_iter()
+                .zip(population)
+                .map(|(fitness, individual)| EffectiveFitness {
+                    individual,
+                    fitness,
+                })
+                .collect()
+        });
+
+        let offspring_count = self.offspring_count.unwrap_or(population.len());
+
+        let offspring = (elites.len()..offspring_count).map(|_| {
+            let parent_a = self.select_parent(rng, population, effective.as_deref());
+
+            // Not drawing anything at the default chance keeps the random
+            // stream (and thus results) the same as without this option
+            let crossover =
+                self.crossover_chance >= 1.0 || rng.gen_bool(self.crossover_chance as _);
+
+            let (mut child, parents_fitness) = if crossover {
+                let mut parent_b = self.select_parent(rng, population, effective.as_deref());
+
+                for _ in 0..self.self_crossover_retries {
+                    if !std::ptr::eq(parent_a, parent_b) {
+                        break;
+                    }
+
+                    parent_b = self.select_parent(rng, population, effective.as_deref());
+                }
+
+                let child = self.crossover_method.crossover(
+                    rng,
+                    parent_a.chromosome(),
+                    parent_b.chromosome(),
+                );
+
+                (child, (parent_a.fitness() + parent_b.fitness()) / 2.0)
+            } else {
+                (parent_a.chromosome().clone(), parent_a.fitness())
+            };
+
+            self.mutation_method.mutate(rng, &mut child);
+
+            if let Some(mean) = &mean {
+                for (gene, mean) in child.iter_mut().zip(mean.iter()) {
+                    *gene += (mean - *gene) * self.regularization;
+                }
+            }
+
+            if let Some(repair) = &self.repair {
+                repair.repair(&mut child);
+            }
+
+            (I::create(child), parents_fitness)
+        });
+
+        let mut offspring: Vec<_> = offspring.collect();
+
+        if self.truncate_offspring {
+            offspring.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+            offspring.truncate(population.len().saturating_sub(elites.len()));
+        }
+
+        if let Some((fraction, factory)) = &self.immigrants {
+            let count = ((fraction * offspring.len() as f32).round() as usize).min(offspring.len());
+
+            let mut worst: Vec<_> = (0..offspring.len()).collect();
+
+            worst.sort_by(|&a, &b| {
+                offspring[a]
+                    .1
+                    .partial_cmp(&offspring[b].1)
+                    .unwrap_or(Ordering::Equal)
+            });
+
+            for idx in worst.into_iter().take(count) {
+                let mut immigrant = factory(rng);
+
+                if let Some(repair) = &self.repair {
+                    repair.repair(&mut immigrant);
+                }
+
+                offspring[idx].0 = I::create(immigrant);
+            }
+        }
+
+        let offspring = offspring.into_iter().map(|(child, _)| child);
+
+        let evolved_population: Vec<_> = elites.into_iter().chain(offspring).collect();
+
+        if self.preserve_best {
+            debug_assert!(
+                Self::contains_best(population, &evolved_population),
+                "The fittest individual got lost"
+            );
+        }
+
+        Ok(evolved_population)
     }
-}
 
-pub struct RouletteWheelSelection;
-impl RouletteWheelSelection {
-    pub fn new() -> Self {
-        Self
+    /// Returns fitness of each individual after fitness sharing and sigma
+    /// scaling; `None` when neither is enabled
+    fn effective_fitness<I>(&self, population: &[I]) -> Option<Vec<f32>>
+    where
+        I: Individual,
+    {
+        if self.fitness_sharing.is_none()
+            && self.sigma_scaling.is_none()
+            && self.fitness_pipeline.is_none()
+        {
+            return None;
+        }
+
+        let fitness = match self.fitness_sharing {
+            Some(sigma) => shared_fitness(population, sigma),
+            None => population
+                .iter()
+                .map(|individual| individual.fitness())
+                .collect(),
+        };
+
+        let fitness = match &self.sigma_scaling {
+            Some(sigma_scaling) => sigma_scaling.scale(&fitness),
+            None => fitness,
+        };
+
+        match &self.fitness_pipeline {
+            Some(fitness_pipeline) => Some(fitness_pipeline.apply(&fitness)),
+            None => Some(fitness),
+        }
     }
-}
 
-impl SelectionMethod for RouletteWheelSelection {
-    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    fn select_parent<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        effective: Option<&'a [EffectiveFitness<'a, I>]>,
+    ) -> &'a I
     where
         I: Individual,
     {
-        population
-            .choose_weighted(rng, |individual| individual.fitness())
-            .expect("Empty population")
+        match effective {
+            Some(effective) => self.selection_method.select(rng, effective).individual,
+            None => self.selection_method.select(rng, population),
+        }
     }
-}
 
-#[derive(Clone, Debug)]
-pub struct Chromosome {
-    genes: Vec<f32>,
-}
-impl Chromosome {
-    pub fn len(&self) -> usize {
-        self.genes.len()
+    /// Checks whether any of the fittest individuals of `population` made it
+    /// (verbatim) into `evolved_population`; compares chromosomes rather
+    /// than fitness, since freshly created individuals haven't been
+    /// evaluated yet.
+    fn contains_best<I>(population: &[I], evolved_population: &[I]) -> bool
+    where
+        I: Individual,
+    {
+        let max_fitness = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        population
+            .iter()
+            .filter(|individual| individual.fitness() >= max_fitness)
+            .any(|best| {
+                evolved_population
+                    .iter()
+                    .any(|individual| individual.chromosome().iter().eq(best.chromosome().iter()))
+            })
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &f32> {
-        self.genes.iter()
+    /// Evolves given population for `generations` rounds, returning the
+    /// final population together with statistics of each new generation.
+    pub fn run<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        initial: Vec<I>,
+        generations: usize,
+    ) -> (Vec<I>, Vec<Statistics>)
+    where
+        I: Individual,
+    {
+        self.run_with_callback(rng, initial, generations, |_, _| ControlFlow::Continue(()))
     }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut f32> {
-        self.genes.iter_mut()
+    /// Like `run()`, but calls `callback` with the index and statistics of
+    /// each new generation; returning `ControlFlow::Break` stops the
+    /// evolution early.
+    pub fn run_with_callback<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        initial: Vec<I>,
+        max_generations: usize,
+        mut callback: impl FnMut(usize, &Statistics) -> ControlFlow<()>,
+    ) -> (Vec<I>, Vec<Statistics>)
+    where
+        I: Individual,
+    {
+        let mut population = initial;
+        let mut statistics = Vec::with_capacity(max_generations);
+
+        for generation in 0..max_generations {
+            population = self.evolve(rng, &population);
+            statistics.push(Statistics::new(&population));
+
+            if callback(generation, &statistics[generation]).is_break() {
+                break;
+            }
+        }
+
+        (population, statistics)
     }
-}
-impl Index<usize> for Chromosome {
-    type Output = f32;
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.genes[index]
+    /// Like `run()`, but also measures how long (in wall-clock time) each
+    /// generation took to evolve.
+    #[cfg(feature = "timing")]
+    pub fn run_timed<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        initial: Vec<I>,
+        generations: usize,
+    ) -> (Vec<I>, Vec<Statistics>, Vec<std::time::Duration>)
+    where
+        I: Individual,
+    {
+        let mut population = initial;
+        let mut statistics = Vec::with_capacity(generations);
+        let mut timings = Vec::with_capacity(generations);
+
+        for _ in 0..generations {
+            let started_at = std::time::Instant::now();
+            population = self.evolve(rng, &population);
+            timings.push(started_at.elapsed());
+
+            statistics.push(Statistics::new(&population));
+        }
+
+        (population, statistics, timings)
     }
-}
-impl FromIterator<f32> for Chromosome {
-    fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
-        Self {
-            genes: iter.into_iter().collect(),
+
+    pub fn config(&self) -> GaConfig<S, C, M>
+    where
+        S: Clone,
+        C: Clone,
+        M: Clone,
+    {
+        GaConfig {
+            selection_method: self.selection_method.clone(),
+            crossover_method: self.crossover_method.clone(),
+            mutation_method: self.mutation_method.clone(),
+            elitism: self.elitism,
+            elite_mutation: self.elite_mutation.clone(),
+            regularization: self.regularization,
+            preserve_best: self.preserve_best,
+            fitness_sharing: self.fitness_sharing,
+            sigma_scaling: self.sigma_scaling.clone(),
+            fitness_pipeline: self.fitness_pipeline.clone(),
+            self_crossover_retries: self.self_crossover_retries,
+            crossover_chance: self.crossover_chance,
+            offspring_count: self.offspring_count,
+            truncate_offspring: self.truncate_offspring,
         }
     }
 }
-impl IntoIterator for Chromosome {
-    type Item = f32;
-    type IntoIter = impl Iterator<Item = f32>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.genes.into_iter()
-    }
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GaError {
+    EmptyPopulation,
 }
 
-#[derive(Clone, Debug)]
-pub struct UniformCrossover;
-impl UniformCrossover {
-    pub fn new() -> Self {
-        Self
+impl fmt::Display for GaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyPopulation => write!(f, "cannot evolve an empty population"),
+        }
     }
 }
-impl CrossoverMethod for UniformCrossover {
-    fn crossover(
-        &self,
-        rng: &mut dyn RngCore,
-        parent_a: &Chromosome,
-        parent_b: &Chromosome,
-    ) -> Chromosome {
-        assert_eq!(parent_a.len(), parent_b.len());
 
-        let parent_a = parent_a.iter();
-        let parent_b = parent_b.iter();
+impl std::error::Error for GaError {}
 
-        parent_a
-            .zip(parent_b)
-            .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
-            .collect()
-    }
+/// Serializable snapshot of `GeneticAlgorithm`'s operators and options,
+/// useful for recording (and later reproducing) an experiment.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GaConfig<S, C, M> {
+    pub selection_method: S,
+    pub crossover_method: C,
+    pub mutation_method: M,
+    pub elitism: usize,
+    pub elite_mutation: Option<GaussianMutation>,
+    pub regularization: f32,
+    pub preserve_best: bool,
+    pub fitness_sharing: Option<f32>,
+    pub sigma_scaling: Option<SigmaScaling>,
+    pub fitness_pipeline: Option<FitnessPipeline>,
+    pub self_crossover_retries: usize,
+    pub crossover_chance: f32,
+    pub offspring_count: Option<usize>,
+    pub truncate_offspring: bool,
 }
+impl<S, C, M> From<GaConfig<S, C, M>> for GeneticAlgorithm<S, C, M>
+where
+    S: SelectionMethod,
+    C: CrossoverMethod,
+    M: MutationMethod,
+{
+    fn from(config: GaConfig<S, C, M>) -> Self {
+        let ga = Self::new(
+            config.selection_method,
+            config.crossover_method,
+            config.mutation_method,
+        )
+        .with_elitism(config.elitism)
+        .with_regularization(config.regularization)
+        .with_preserve_best(config.preserve_best)
+        .with_self_crossover_retries(config.self_crossover_retries)
+        .with_crossover_chance(config.crossover_chance)
+        .with_truncate_offspring(config.truncate_offspring);
 
-#[derive(Clone, Debug)]
-pub struct GaussianMutation {
-    /// Probability of changing a gene:
-    /// - 0.0 = no genes will be touched
-    /// - 1.0 = all genes will be touched
-    chance: f32,
+        let ga = match config.offspring_count {
+            Some(offspring_count) => ga.with_offspring_count(offspring_count),
+            None => ga,
+        };
 
-    /// Magnitude of that change:
-    /// - 0.0 = touched genes will not be modified
-    /// - 3.0 = touched genes will be += or -= by at most 3.0
-    coeff: f32,
-}
-impl GaussianMutation {
-    pub fn new(chance: f32, coeff: f32) -> Self {
-        assert!((0.0..=1.0).contains(&chance));
+        let ga = match config.elite_mutation {
+            Some(elite_mutation) => ga.with_elite_mutation(elite_mutation),
+            None => ga,
+        };
 
-        Self { chance, coeff }
-    }
-}
-impl MutationMethod for GaussianMutation {
-    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
-        for gene in child.iter_mut() {
-            let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
+        let ga = match config.fitness_sharing {
+            Some(sigma) => ga.with_fitness_sharing(sigma),
+            None => ga,
+        };
 
-            if rng.gen_bool(self.chance as _) {
-                *gene += sign * self.coeff * rng.gen::<f32>();
-            }
+        let ga = match config.sigma_scaling {
+            Some(sigma_scaling) => ga.with_sigma_scaling(sigma_scaling),
+            None => ga,
+        };
+
+        match config.fitness_pipeline {
+            Some(fitness_pipeline) => ga.with_fitness_pipeline(fitness_pipeline),
+            None => ga,
         }
     }
 }
 
-#[cfg(test)]
-mod genetic_algorithm {
-    use rand_chacha::ChaCha8Rng;
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    min_fitness: f32,
+    max_fitness: f32,
+    avg_fitness: f32,
 
-    use self::selection::TestIndividual;
+    /// Fitness of each individual, in ascending order
+    sorted_fitness: Vec<f32>,
+}
+impl Statistics {
+    /// Header of the CSV produced by `to_csv()`
+    pub const CSV_HEADER: &'static str = "generation,min,max,avg,median";
 
-    use super::*;
+    pub fn new<I>(population: &[I]) -> Self
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
 
-    mod selection {
-        use super::*;
-        use rand_chacha::ChaCha8Rng;
-        use std::collections::BTreeMap;
+        let mut min_fitness = population[0].fitness();
+        let mut max_fitness = min_fitness;
+        let mut sum_fitness = 0.0;
 
-        #[cfg(test)]
-        #[derive(Clone, Debug, PartialEq)]
-        pub enum TestIndividual {
-            WithChromosome { chromosome: Chromosome },
-            WithFitness { fitness: f32 },
+        for individual in population {
+            let fitness = individual.fitness();
+
+            min_fitness = min_fitness.min(fitness);
+            max_fitness = max_fitness.max(fitness);
+            sum_fitness += fitness;
         }
 
-        #[cfg(test)]
-        impl TestIndividual {
-            pub fn new(fitness: f32) -> Self {
-                Self::WithFitness { fitness }
-            }
+        let mut sorted_fitness: Vec<_> = population.iter().map(|i| i.fitness()).collect();
+        sorted_fitness.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        Self {
+            min_fitness,
+            max_fitness,
+            avg_fitness: sum_fitness / (population.len() as f32),
+            sorted_fitness,
         }
+    }
 
-        #[cfg(test)]
+    pub fn min_fitness(&self) -> f32 {
+        self.min_fitness
+    }
+
+    pub fn max_fitness(&self) -> f32 {
+        self.max_fitness
+    }
+
+    pub fn avg_fitness(&self) -> f32 {
+        self.avg_fitness
+    }
+
+    /// Returns a line of CSV (without the trailing newline), following
+    /// `CSV_HEADER`
+    pub fn to_csv_row(&self, generation: usize) -> String {
+        format!(
+            "{},{},{},{},{}",
+            generation,
+            self.min_fitness,
+            self.max_fitness,
+            self.avg_fitness,
+            self.percentile(50.0)
+        )
+    }
+
+    /// Returns statistics of consecutive generations (e.g. as returned from
+    /// `GeneticAlgorithm::run()`) as CSV, including the header
+    pub fn to_csv(statistics: &[Statistics]) -> String {
+        let mut csv = String::from(Self::CSV_HEADER);
+        csv.push('\n');
+
+        for (generation, statistics) in statistics.iter().enumerate() {
+            csv.push_str(&statistics.to_csv_row(generation));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Returns the `p`-th percentile (`0.0..=100.0`) of fitness, linearly
+    /// interpolating between the two nearest individuals
+    pub fn percentile(&self, p: f32) -> f32 {
+        assert!((0.0..=100.0).contains(&p));
+
+        let rank = p / 100.0 * (self.sorted_fitness.len() - 1) as f32;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+
+        let lower_fitness = self.sorted_fitness[lower];
+        let upper_fitness = self.sorted_fitness[upper];
+
+        lower_fitness + (upper_fitness - lower_fitness) * (rank - lower as f32)
+    }
+}
+
+/// Rescales fitness as `1 + (fitness - mean) / (2 * stddev)`, so that
+/// selection pressure doesn't depend on the absolute scale (or spread) of
+/// fitness values
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SigmaScaling {
+    /// Lowest fitness an individual can get scaled down to, so that
+    /// everyone keeps some chance of being selected
+    min: f32,
+}
+impl SigmaScaling {
+    pub fn new() -> Self {
+        Self { min: 0.1 }
+    }
+
+    pub fn with_min(mut self, min: f32) -> Self {
+        assert!(min >= 0.0);
+
+        self.min = min;
+        self
+    }
+
+    pub fn scale(&self, fitness: &[f32]) -> Vec<f32> {
+        if fitness.is_empty() {
+            return Vec::new();
+        }
+
+        let len = fitness.len() as f32;
+        let mean = fitness.iter().sum::<f32>() / len;
+        let stddev = (fitness.iter().map(|f| (f - mean).powi(2)).sum::<f32>() / len).sqrt();
+
+        fitness
+            .iter()
+            .map(|f| {
+                if stddev > 0.0 {
+                    (1.0 + (f - mean) / (2.0 * stddev)).max(self.min)
+                } else {
+                    1.0
+                }
+            })
+            .collect()
+    }
+}
+impl Default for SigmaScaling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Single step of `FitnessPipeline`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FitnessTransform {
+    /// Adds a constant
+    Shift(f32),
+
+    /// Multiplies by a constant
+    Scale(f32),
+
+    /// Raises to given power (fitness should be non-negative by then, unless
+    /// the power is an integer)
+    Power(f32),
+
+    /// Replaces fitness with its rank - 1.0 for the least fit individual(s),
+    /// and so on; equally fit individuals get the same rank
+    Rank,
+
+    /// Clamps into `min..=max`
+    Clamp(f32, f32),
+}
+
+/// Sequence of transforms applied, in order, to fitness of the whole
+/// population before selection
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FitnessPipeline(pub Vec<FitnessTransform>);
+impl FitnessPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn then(mut self, transform: FitnessTransform) -> Self {
+        self.0.push(transform);
+        self
+    }
+
+    pub fn apply(&self, fitness: &[f32]) -> Vec<f32> {
+        self.0
+            .iter()
+            .fold(fitness.to_vec(), |fitness, transform| match *transform {
+                FitnessTransform::Shift(c) => fitness.iter().map(|f| f + c).collect(),
+                FitnessTransform::Scale(c) => fitness.iter().map(|f| f * c).collect(),
+                FitnessTransform::Power(p) => fitness.iter().map(|f| f.powf(p)).collect(),
+                FitnessTransform::Rank => fitness
+                    .iter()
+                    .map(|f| 1.0 + fitness.iter().filter(|other| *other < f).count() as f32)
+                    .collect(),
+                FitnessTransform::Clamp(min, max) => {
+                    fitness.iter().map(|f| f.clamp(min, max)).collect()
+                }
+            })
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouletteWheelSelection {
+    /// Constant added to everyone's fitness, so that selection works even
+    /// when the whole population has zero fitness
+    fitness_floor: f32,
+}
+impl RouletteWheelSelection {
+    pub fn new() -> Self {
+        Self { fitness_floor: 0.0 }
+    }
+
+    pub fn with_fitness_floor(mut self, fitness_floor: f32) -> Self {
+        assert!(fitness_floor >= 0.0);
+
+        self.fitness_floor = fitness_floor;
+        self
+    }
+}
+
+impl SelectionMethod for RouletteWheelSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "Empty population");
+
+        // Weighed in `f64`, so that tiny fitnesses don't get lost next to
+        // large totals
+        population
+            .choose_weighted(rng, |individual| {
+                let fitness = individual.fitness();
+
+                assert!(fitness >= 0.0, "Negative fitness");
+
+                fitness as f64 + self.fitness_floor as f64
+            })
+            .expect("Population has no fitness")
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TournamentSelection {
+    /// Number of individuals competing in each tournament
+    size: usize,
+
+    /// Whether an individual may fill at most one slot of a tournament
+    without_replacement: bool,
+}
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+
+        Self {
+            size,
+            without_replacement: false,
+        }
+    }
+
+    pub fn without_replacement(mut self, without_replacement: bool) -> Self {
+        self.without_replacement = without_replacement;
+        self
+    }
+
+    fn contestants(&self, rng: &mut dyn RngCore, population_len: usize) -> Vec<usize> {
+        if self.without_replacement {
+            assert!(self.size <= population_len);
+
+            rand::seq::index::sample(rng, population_len, self.size).into_vec()
+        } else {
+            (0..self.size)
+                .map(|_| rng.gen_range(0..population_len))
+                .collect()
+        }
+    }
+}
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        self.contestants(rng, population.len())
+            .into_iter()
+            .map(|idx| &population[idx])
+            .max_by(|a, b| {
+                a.fitness()
+                    .partial_cmp(&b.fitness())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .expect("Empty tournament")
+    }
+}
+
+/// Always selects the fittest individual (the first one, on ties) - useless
+/// for actual evolution, but handy for making it predictable when
+/// debugging other operators.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BestSelection;
+impl BestSelection {
+    pub fn new() -> Self {
+        Self
+    }
+}
+impl SelectionMethod for BestSelection {
+    fn select<'a, I>(&self, _: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        best_individual(population)
+    }
+}
+
+/// Object-safe counterpart of `SelectionMethod` - since selection methods
+/// only ever look at fitness, it picks an index out of fitness values.
+///
+/// Implemented for every `SelectionMethod`, allowing to choose one at
+/// runtime (see `BoxedSelection`).
+pub trait DynSelectionMethod {
+    fn select_index(&self, rng: &mut dyn RngCore, fitnesses: &[f32]) -> usize;
+}
+
+impl<S> DynSelectionMethod for S
+where
+    S: SelectionMethod,
+{
+    fn select_index(&self, rng: &mut dyn RngCore, fitnesses: &[f32]) -> usize {
+        let population: Vec<_> = fitnesses.iter().copied().map(FitnessOnly).collect();
+        let selected = self.select(rng, &population);
+
+        population
+            .iter()
+            .position(|individual| std::ptr::eq(individual, selected))
+            .expect("Selection method returned an individual from outside the population")
+    }
+}
+
+/// Individual carrying just the fitness, used to drive selection methods
+/// through `DynSelectionMethod`
+struct FitnessOnly(f32);
+
+impl Individual for FitnessOnly {
+    fn chromosome(&self) -> &Chromosome {
+        panic!("not supported for FitnessOnly")
+    }
+
+    fn fitness(&self) -> f32 {
+        self.0
+    }
+
+    fn create(_: Chromosome) -> Self {
+        panic!("not supported for FitnessOnly")
+    }
+}
+
+/// Selection method chosen at runtime
+pub struct BoxedSelection(pub Box<dyn DynSelectionMethod>);
+
+impl BoxedSelection {
+    pub fn new(selection_method: impl SelectionMethod + 'static) -> Self {
+        Self(Box::new(selection_method))
+    }
+}
+
+impl SelectionMethod for BoxedSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        let fitnesses: Vec<_> = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .collect();
+
+        &population[self.0.select_index(rng, &fitnesses)]
+    }
+}
+
+/// Describes behavior of an individual, for `NoveltySelection`
+type BehaviorDescriptor = Box<dyn Fn(&Chromosome) -> Vec<f32> + Send + Sync>;
+
+/// Selects individuals proportionally to their novelty instead of fitness,
+/// where novelty is the mean distance between an individual's behavior
+/// (as described by the caller) and behaviors of its `k` nearest
+/// neighbours.
+///
+/// Novelty is recomputed on each selection, which takes quadratic time in
+/// the population's size; requires individuals that actually carry
+/// chromosomes (so it can't be wrapped in `BoxedSelection`).
+pub struct NoveltySelection {
+    k: usize,
+    descriptor: BehaviorDescriptor,
+}
+impl NoveltySelection {
+    pub fn new(
+        k: usize,
+        descriptor: impl Fn(&Chromosome) -> Vec<f32> + Send + Sync + 'static,
+    ) -> Self {
+        assert!(k > 0);
+
+        Self {
+            k,
+            descriptor: Box::new(descriptor),
+        }
+    }
+
+    /// Returns novelty of each individual
+    pub fn novelty<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: Individual,
+    {
+        let behaviors: Vec<_> = population
+            .iter()
+            .map(|individual| (self.descriptor)(individual.chromosome()))
+            .collect();
+
+        behaviors
+            .iter()
+            .enumerate()
+            .map(|(idx, behavior)| {
+                let mut distances: Vec<_> = behaviors
+                    .iter()
+                    .enumerate()
+                    .filter(|(other_idx, _)| *other_idx != idx)
+                    .map(|(_, other)| {
+                        behavior
+                            .iter()
+                            .zip(other)
+                            .map(|(a, b)| (a - b).powi(2))
+                            .sum::<f32>()
+                            .sqrt()
+                    })
+                    .collect();
+
+                if distances.is_empty() {
+                    return 0.0;
+                }
+
+                distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                distances.truncate(self.k);
+
+                distances.iter().sum::<f32>() / distances.len() as f32
+            })
+            .collect()
+    }
+}
+impl SelectionMethod for NoveltySelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "Empty population");
+
+        let novelty = self.novelty(population);
+        let total: f32 = novelty.iter().sum();
+
+        // Everyone behaves the same, so no one is more novel than others
+        if total <= 0.0 {
+            return &population[rng.gen_range(0..population.len())];
+        }
+
+        let mut threshold = rng.gen_range(0.0..total);
+
+        for (individual, novelty) in population.iter().zip(novelty) {
+            if threshold < novelty {
+                return individual;
+            }
+
+            threshold -= novelty;
+        }
+
+        &population[population.len() - 1]
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chromosome {
+    genes: Vec<f32>,
+}
+impl Chromosome {
+    pub fn len(&self) -> usize {
+        self.genes.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &f32> {
+        self.genes.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut f32> {
+        self.genes.iter_mut()
+    }
+
+    /// Returns the L2 norm of genes
+    pub fn norm(&self) -> f32 {
+        self.iter().map(|gene| gene.powi(2)).sum::<f32>().sqrt()
+    }
+
+    /// Scales genes to unit norm; zero chromosomes are left as-is
+    pub fn normalize(&mut self) {
+        let norm = self.norm();
+
+        if norm > 0.0 {
+            for gene in self.iter_mut() {
+                *gene /= norm;
+            }
+        }
+    }
+
+    /// Returns the Euclidean distance between both chromosomes
+    pub fn distance(&self, other: &Self) -> f32 {
+        assert_eq!(self.len(), other.len());
+
+        self.iter()
+            .zip(other.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+}
+impl Index<usize> for Chromosome {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.genes[index]
+    }
+}
+impl FromIterator<f32> for Chromosome {
+    fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
+        Self {
+            genes: iter.into_iter().collect(),
+        }
+    }
+}
+impl IntoIterator for Chromosome {
+    type Item = f32;
+    type IntoIter = impl Iterator<Item = f32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.genes.into_iter()
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UniformCrossover {
+    /// Probability of a gene being taken from `parent_a`
+    bias: f32,
+}
+impl UniformCrossover {
+    pub fn new() -> Self {
+        Self { bias: 0.5 }
+    }
+
+    pub fn with_bias(mut self, bias: f32) -> Self {
+        assert!((0.0..=1.0).contains(&bias));
+
+        self.bias = bias;
+        self
+    }
+}
+impl CrossoverMethod for UniformCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let parent_a = parent_a.iter();
+        let parent_b = parent_b.iter();
+
+        parent_a
+            .zip(parent_b)
+            .map(|(&a, &b)| if rng.gen_bool(self.bias as f64) { a } else { b })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SinglePointCrossover;
+impl SinglePointCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let point = rng.gen_range(0..=parent_a.len());
+
+        parent_a
+            .iter()
+            .take(point)
+            .chain(parent_b.iter().skip(point))
+            .copied()
+            .collect()
+    }
+}
+
+/// Crossover for chromosomes encoding neural networks, which takes each
+/// layer's genes wholesale from one parent or the other, so that layers
+/// don't get scrambled.
+///
+/// Since chromosomes carry no topology, it has to be provided up front as
+/// the number of genes in each layer (e.g. via the neural network's
+/// `LayerTopology::weight_counts()`, or `LayerTopology::gene_counts()` for
+/// chromosomes which also encode activations).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerAwareCrossover {
+    layers: Vec<usize>,
+}
+impl LayerAwareCrossover {
+    pub fn new(layers: Vec<usize>) -> Self {
+        Self { layers }
+    }
+}
+impl CrossoverMethod for LayerAwareCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+        assert_eq!(
+            parent_a.len(),
+            self.layers.iter().sum::<usize>(),
+            "chromosome doesn't match the topology"
+        );
+
+        let mut genes = Vec::with_capacity(parent_a.len());
+        let mut offset = 0;
+
+        for &layer in &self.layers {
+            let parent = if rng.gen_bool(0.5) {
+                parent_a
+            } else {
+                parent_b
+            };
+
+            genes.extend(parent.iter().skip(offset).take(layer));
+            offset += layer;
+        }
+
+        genes.into_iter().collect()
+    }
+}
+
+/// Tells, for each gene of `child`, which parent it came from:
+/// - `Some(true)` = parent A
+/// - `Some(false)` = parent B
+/// - `None` = can't tell, because both parents have the same gene there
+///
+/// Panics if a gene comes from neither parent (e.g. it got mutated).
+pub fn crossover_source_mask(
+    child: &Chromosome,
+    parent_a: &Chromosome,
+    parent_b: &Chromosome,
+) -> Vec<Option<bool>> {
+    assert_eq!(child.len(), parent_a.len());
+    assert_eq!(child.len(), parent_b.len());
+
+    child
+        .iter()
+        .zip(parent_a.iter().zip(parent_b.iter()))
+        .enumerate()
+        .map(|(idx, (gene, (a, b)))| match (gene == a, gene == b) {
+            (true, true) => None,
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => panic!("Gene #{} comes from neither parent", idx),
+        })
+        .collect()
+}
+
+/// Measures how much given crossover method breaks up combinations of
+/// genes - i.e. the fraction of adjacent gene pairs in a child that come
+/// from different parents, averaged over `trials` crossovers.
+///
+/// Pairs where the source of a gene can't be told (see
+/// `crossover_source_mask()`) are not counted; parents are best chosen so
+/// that they differ at each gene.
+pub fn crossover_disruption(
+    method: &impl CrossoverMethod,
+    rng: &mut dyn RngCore,
+    parent_a: &Chromosome,
+    parent_b: &Chromosome,
+    trials: usize,
+) -> f32 {
+    let mut disrupted = 0;
+    let mut pairs = 0;
+
+    for _ in 0..trials {
+        let child = method.crossover(rng, parent_a, parent_b);
+        let mask = crossover_source_mask(&child, parent_a, parent_b);
+
+        for pair in mask.windows(2) {
+            if let [Some(a), Some(b)] = pair {
+                pairs += 1;
+
+                if a != b {
+                    disrupted += 1;
+                }
+            }
+        }
+    }
+
+    if pairs == 0 {
+        0.0
+    } else {
+        disrupted as f32 / pairs as f32
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GaussianMutation {
+    /// Probability of changing a gene:
+    /// - 0.0 = no genes will be touched
+    /// - 1.0 = all genes will be touched
+    chance: f32,
+
+    /// Magnitude of that change:
+    /// - 0.0 = touched genes will not be modified
+    /// - 3.0 = touched genes will be += or -= by at most 3.0
+    coeff: f32,
+}
+impl GaussianMutation {
+    pub fn new(chance: f32, coeff: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+
+        Self { chance, coeff }
+    }
+}
+impl GaussianMutation {
+    fn mutate_gene(&self, rng: &mut dyn RngCore, gene: &mut f32) {
+        let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
+
+        if rng.gen_bool(self.chance as _) {
+            *gene += sign * self.coeff * rng.gen::<f32>();
+        }
+    }
+}
+impl MutationMethod for GaussianMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        for gene in child.iter_mut() {
+            self.mutate_gene(rng, gene);
+        }
+    }
+}
+
+/// Mutates biases and weights of an encoded neural network with separate
+/// settings.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoleAwareMutation {
+    bias: GaussianMutation,
+    weight: GaussianMutation,
+
+    /// Genes whose mask is `true` are biases, the rest are weights (e.g.
+    /// built from the neural network's `weight_roles()`, or `gene_roles()`
+    /// for chromosomes which also encode activations)
+    biases: Vec<bool>,
+}
+impl RoleAwareMutation {
+    pub fn new(bias: GaussianMutation, weight: GaussianMutation, biases: Vec<bool>) -> Self {
+        Self {
+            bias,
+            weight,
+            biases,
+        }
+    }
+}
+impl MutationMethod for RoleAwareMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        assert_eq!(child.len(), self.biases.len());
+
+        for (gene, &is_bias) in child.iter_mut().zip(&self.biases) {
+            if is_bias {
+                self.bias.mutate_gene(rng, gene);
+            } else {
+                self.weight.mutate_gene(rng, gene);
+            }
+        }
+    }
+}
+
+/// Like `GaussianMutation`, but with the magnitude of change scaled per
+/// gene by `1 / (1 + variance)`, so that genes which have collapsed across
+/// the population (see `population_gene_variance()`) get perturbed more than
+/// still-diverse ones.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveMutation {
+    chance: f32,
+    coeff: f32,
+    variances: Vec<f32>,
+}
+impl AdaptiveMutation {
+    pub fn new(chance: f32, coeff: f32, variances: Vec<f32>) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+
+        Self {
+            chance,
+            coeff,
+            variances,
+        }
+    }
+
+    pub fn from_population<I>(chance: f32, coeff: f32, population: &[I]) -> Self
+    where
+        I: Individual,
+    {
+        Self::new(chance, coeff, population_gene_variance(population))
+    }
+}
+impl MutationMethod for AdaptiveMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        assert_eq!(child.len(), self.variances.len());
+
+        for (gene, variance) in child.iter_mut().zip(&self.variances) {
+            GaussianMutation::new(self.chance, self.coeff / (1.0 + variance))
+                .mutate_gene(rng, gene);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaskedMutation<M> {
+    inner: M,
+
+    /// Genes whose mask is `true` are left untouched by `inner`
+    frozen: Vec<bool>,
+}
+impl<M> MaskedMutation<M> {
+    pub fn new(inner: M, frozen: Vec<bool>) -> Self {
+        Self { inner, frozen }
+    }
+}
+impl<M> MutationMethod for MaskedMutation<M>
+where
+    M: MutationMethod,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        assert_eq!(child.len(), self.frozen.len());
+
+        let original = child.clone();
+
+        self.inner.mutate(rng, child);
+
+        for ((gene, original), &frozen) in child.iter_mut().zip(original).zip(&self.frozen) {
+            if frozen {
+                *gene = original;
+            }
+        }
+    }
+}
+
+/// Applies a sequence of mutation methods, one after another
+pub struct ChainMutation(pub Vec<Box<dyn MutationMethod>>);
+
+impl ChainMutation {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn then(mut self, mutation_method: impl MutationMethod + 'static) -> Self {
+        self.0.push(Box::new(mutation_method));
+        self
+    }
+}
+
+impl Default for ChainMutation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MutationMethod for ChainMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        for mutation_method in &self.0 {
+            mutation_method.mutate(rng, child);
+        }
+    }
+}
+
+/// Keeps genes non-negative and summing up to one (e.g. for chromosomes
+/// encoding proportions)
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimplexRepair;
+impl SimplexRepair {
+    pub fn new() -> Self {
+        Self
+    }
+}
+impl Repair for SimplexRepair {
+    fn repair(&self, chromosome: &mut Chromosome) {
+        for gene in chromosome.iter_mut() {
+            *gene = gene.max(0.0);
+        }
+
+        let sum: f32 = chromosome.iter().sum();
+        let len = chromosome.len() as f32;
+
+        for gene in chromosome.iter_mut() {
+            *gene = if sum > 0.0 { *gene / sum } else { 1.0 / len };
+        }
+    }
+}
+
+/// Returns the mean pairwise Euclidean distance between chromosomes of
+/// given population.
+///
+/// Requires individuals that actually carry chromosomes - e.g. the
+/// fitness-only individuals used to test selection methods will panic.
+pub fn population_diversity<I>(population: &[I]) -> f32
+where
+    I: Individual,
+{
+    let mut total = 0.0;
+    let mut pairs = 0;
+
+    for (idx, a) in population.iter().enumerate() {
+        for b in &population[idx + 1..] {
+            total += a.chromosome().distance(b.chromosome());
+            pairs += 1;
+        }
+    }
+
+    if pairs == 0 {
+        0.0
+    } else {
+        total / pairs as f32
+    }
+}
+
+/// Returns whether all chromosomes are within `epsilon` (Euclidean
+/// distance) of each other - in which case selection and crossover can't
+/// make any progress, and it's up to the caller to inject some diversity.
+///
+/// Populations with fewer than two individuals are considered degenerate.
+pub fn is_population_degenerate<I>(population: &[I], epsilon: f32) -> bool
+where
+    I: Individual,
+{
+    population.iter().enumerate().all(|(idx, a)| {
+        population[idx + 1..]
+            .iter()
+            .all(|b| a.chromosome().distance(b.chromosome()) <= epsilon)
+    })
+}
+
+/// Returns variance of each gene across given population.
+///
+/// Panics if chromosomes differ in length; requires individuals that
+/// actually carry chromosomes.
+pub fn population_gene_variance<I>(population: &[I]) -> Vec<f32>
+where
+    I: Individual,
+{
+    let mean = population_centroid(population);
+
+    let mut variances = vec![0.0; mean.len()];
+
+    for individual in population {
+        for ((variance, gene), mean) in variances
+            .iter_mut()
+            .zip(individual.chromosome().iter())
+            .zip(mean.iter())
+        {
+            *variance += (gene - mean).powi(2);
+        }
+    }
+
+    variances
+        .into_iter()
+        .map(|variance| variance / population.len() as f32)
+        .collect()
+}
+
+/// Returns the principal component of chromosomes (i.e. the unit direction
+/// in gene space along which the population is spread the most), computed
+/// by power iteration on the covariance matrix.
+///
+/// The sign is chosen so that the largest (by magnitude) element is
+/// positive; returns all zeros if the population has no spread at all.
+pub fn population_principal_component<I>(population: &[I]) -> Vec<f32>
+where
+    I: Individual,
+{
+    const ITERATIONS: usize = 100;
+
+    let mean = population_centroid(population);
+
+    let centered: Vec<Vec<f32>> = population
+        .iter()
+        .map(|individual| {
+            individual
+                .chromosome()
+                .iter()
+                .zip(mean.iter())
+                .map(|(gene, mean)| gene - mean)
+                .collect()
+        })
+        .collect();
+
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    // Starting from the most outlying chromosome keeps the result
+    // deterministic, while (unlike e.g. all ones) never being orthogonal
+    // to the principal component
+    let mut component = centered
+        .iter()
+        .max_by(|a, b| norm(a).partial_cmp(&norm(b)).unwrap_or(Ordering::Equal))
+        .cloned()
+        .unwrap_or_default();
+
+    for _ in 0..ITERATIONS {
+        let length = norm(&component);
+
+        if length == 0.0 {
+            return vec![0.0; mean.len()];
+        }
+
+        let direction: Vec<_> = component.iter().map(|x| x / length).collect();
+        let mut next = vec![0.0; mean.len()];
+
+        for row in &centered {
+            let projection: f32 = row.iter().zip(&direction).map(|(a, b)| a * b).sum();
+
+            for (next, x) in next.iter_mut().zip(row) {
+                *next += x * projection;
+            }
+        }
+
+        component = next;
+    }
+
+    let length = norm(&component);
+
+    if length == 0.0 {
+        return vec![0.0; mean.len()];
+    }
+
+    let largest = component
+        .iter()
+        .copied()
+        .max_by(|a, b| a.abs().partial_cmp(&b.abs()).unwrap_or(Ordering::Equal))
+        .unwrap_or_default();
+
+    let sign = if largest < 0.0 { -1.0 } else { 1.0 };
+
+    component.iter().map(|x| sign * x / length).collect()
+}
+
+/// Returns the elementwise mean of all chromosomes in given population.
+///
+/// Panics if chromosomes differ in length; requires individuals that
+/// actually carry chromosomes - e.g. `TestIndividual::WithFitness` used to
+/// test selection methods will panic.
+pub fn population_centroid<I>(population: &[I]) -> Chromosome
+where
+    I: Individual,
+{
+    assert!(!population.is_empty());
+
+    let len = population[0].chromosome().len();
+    let mut genes = vec![0.0; len];
+
+    for individual in population {
+        let chromosome = individual.chromosome();
+
+        assert_eq!(chromosome.len(), len);
+
+        for (sum, gene) in genes.iter_mut().zip(chromosome.iter()) {
+            *sum += gene;
+        }
+    }
+
+    genes
+        .into_iter()
+        .map(|sum| sum / population.len() as f32)
+        .collect()
+}
+
+/// Keeps track of the last few best chromosomes to find genes that have
+/// stopped changing (i.e. got fixated) across generations.
+#[derive(Clone, Debug)]
+pub struct GeneTracker {
+    window: usize,
+    history: VecDeque<Chromosome>,
+}
+impl GeneTracker {
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0);
+
+        Self {
+            window,
+            history: VecDeque::with_capacity(window),
+        }
+    }
+
+    pub fn push(&mut self, chromosome: Chromosome) {
+        if let Some(last) = self.history.back() {
+            assert_eq!(last.len(), chromosome.len());
+        }
+
+        if self.history.len() == self.window {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(chromosome);
+    }
+
+    /// Returns variance of each gene over the tracked window
+    pub fn variances(&self) -> Vec<f32> {
+        let genes = match self.history.front() {
+            Some(chromosome) => chromosome.len(),
+            None => return Vec::new(),
+        };
+
+        let n = self.history.len() as f32;
+
+        (0..genes)
+            .map(|idx| {
+                let mean = self.history.iter().map(|c| c[idx]).sum::<f32>() / n;
+
+                self.history
+                    .iter()
+                    .map(|c| (c[idx] - mean).powi(2))
+                    .sum::<f32>()
+                    / n
+            })
+            .collect()
+    }
+
+    /// Returns indices of genes whose variance is below `threshold`
+    pub fn fixated(&self, threshold: f32) -> Vec<usize> {
+        self.variances()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, variance)| *variance < threshold)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+/// Keeps track of generations whose best fitness dropped compared to the
+/// previous generation - which, with elitism enabled, hints at elites
+/// getting lost.
+#[derive(Clone, Debug, Default)]
+pub struct RegressionMonitor {
+    generation: usize,
+    last_max_fitness: Option<f32>,
+    regressions: Vec<usize>,
+}
+impl RegressionMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records statistics of the next generation, returning whether its
+    /// best fitness regressed
+    pub fn push(&mut self, statistics: &Statistics) -> bool {
+        let max_fitness = statistics.max_fitness();
+
+        let regressed = match self.last_max_fitness {
+            Some(last_max_fitness) => max_fitness < last_max_fitness,
+            None => false,
+        };
+
+        if regressed {
+            self.regressions.push(self.generation);
+        }
+
+        self.generation += 1;
+        self.last_max_fitness = Some(max_fitness);
+
+        regressed
+    }
+
+    /// Returns indices of generations (counting from zero, in the order
+    /// they were pushed) whose best fitness regressed
+    pub fn regressions(&self) -> &[usize] {
+        &self.regressions
+    }
+}
+
+/// Memoizes fitness of chromosomes, so that ones reappearing across
+/// generations (e.g. thanks to elitism) don't get re-evaluated.
+///
+/// Chromosomes are keyed by the exact bit patterns of their genes.
+#[derive(Clone, Debug, Default)]
+pub struct FitnessCache {
+    entries: HashMap<Vec<u32>, f32>,
+    hits: usize,
+    misses: usize,
+}
+impl FitnessCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns cached fitness of `chromosome`, calling `evaluate` only if
+    /// it hasn't been seen before
+    pub fn get_or_evaluate(
+        &mut self,
+        chromosome: &Chromosome,
+        evaluate: impl FnOnce(&Chromosome) -> f32,
+    ) -> f32 {
+        let key: Vec<_> = chromosome.iter().map(|gene| gene.to_bits()).collect();
+
+        if let Some(&fitness) = self.entries.get(&key) {
+            self.hits += 1;
+            return fitness;
+        }
+
+        self.misses += 1;
+
+        let fitness = evaluate(chromosome);
+        self.entries.insert(key, fitness);
+        fitness
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+/// Returns selection intensity - how much fitter the selected parents are
+/// than the population they were selected from, measured in the population's
+/// standard deviations: `(mean_selected - mean_population) / stddev_population`.
+///
+/// Returns zero for populations with no fitness variance.
+pub fn selection_intensity(population: &[f32], selected: &[f32]) -> f32 {
+    assert!(!population.is_empty());
+    assert!(!selected.is_empty());
+
+    let mean = |fitness: &[f32]| fitness.iter().sum::<f32>() / fitness.len() as f32;
+
+    let population_mean = mean(population);
+
+    let population_stddev = (population
+        .iter()
+        .map(|fitness| (fitness - population_mean).powi(2))
+        .sum::<f32>()
+        / population.len() as f32)
+        .sqrt();
+
+    if population_stddev == 0.0 {
+        return 0.0;
+    }
+
+    (mean(selected) - population_mean) / population_stddev
+}
+
+/// Outcome of a single configuration of `tournament_pressure_sweep()`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PressureSweepResult {
+    pub tournament_size: usize,
+
+    /// Number of generations it took for the best fitness to reach the
+    /// target; `None` if it didn't get there within `max_generations`
+    pub convergence_generation: Option<usize>,
+
+    /// Best fitness of the last evolved generation
+    pub best_fitness: f32,
+}
+
+/// Runs the same problem with `TournamentSelection` of each of given
+/// `sizes`, so that the effect of selection pressure on convergence can be
+/// compared.
+///
+/// For each size, `ga` builds the algorithm around the selection method and
+/// `initial` provides the starting population; evolution stops as soon as
+/// the best fitness reaches `target_fitness`, or after `max_generations`.
+pub fn tournament_pressure_sweep<I, C, M>(
+    rng: &mut dyn RngCore,
+    sizes: &[usize],
+    ga: impl Fn(TournamentSelection) -> GeneticAlgorithm<TournamentSelection, C, M>,
+    initial: impl Fn() -> Vec<I>,
+    max_generations: usize,
+    target_fitness: f32,
+) -> Vec<PressureSweepResult>
+where
+    I: Individual,
+    C: CrossoverMethod,
+    M: MutationMethod,
+{
+    sizes
+        .iter()
+        .map(|&tournament_size| {
+            let ga = ga(TournamentSelection::new(tournament_size));
+            let mut convergence_generation = None;
+
+            let (_, statistics) =
+                ga.run_with_callback(rng, initial(), max_generations, |generation, stats| {
+                    if stats.max_fitness() >= target_fitness {
+                        convergence_generation = Some(generation + 1);
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                });
+
+            PressureSweepResult {
+                tournament_size,
+                convergence_generation,
+                best_fitness: statistics
+                    .last()
+                    .map(|stats| stats.max_fitness())
+                    .unwrap_or(f32::NEG_INFINITY),
+            }
+        })
+        .collect()
+}
+
+/// Estimates how many offspring (out of `offspring` in total) each
+/// individual is expected to produce under given selection method, i.e.
+/// `offspring * probability of getting selected`.
+///
+/// Probabilities are estimated by Monte Carlo sampling (a fixed number of
+/// selections per individual), so results are approximate.
+pub fn expected_offspring<I>(
+    rng: &mut dyn RngCore,
+    method: &impl SelectionMethod,
+    population: &[I],
+    offspring: usize,
+) -> Vec<f32>
+where
+    I: Individual,
+{
+    const SAMPLES_PER_INDIVIDUAL: usize = 1000;
+
+    let samples = SAMPLES_PER_INDIVIDUAL * population.len();
+    let mut hits = vec![0; population.len()];
+
+    for _ in 0..samples {
+        let selected = method.select(rng, population);
+
+        let idx = population
+            .iter()
+            .position(|individual| std::ptr::eq(individual, selected))
+            .expect("selection method returned an individual from outside the population");
+
+        hits[idx] += 1;
+    }
+
+    hits.into_iter()
+        .map(|hits| offspring as f32 * hits as f32 / samples as f32)
+        .collect()
+}
+
+/// Returns the fittest individual in a single pass; when there are a few
+/// equally fit ones, the first of them (with the lowest index) is returned.
+pub fn best_individual<I>(population: &[I]) -> &I
+where
+    I: Individual,
+{
+    let mut best = population.first().expect("Empty population");
+
+    for individual in &population[1..] {
+        if individual.fitness() > best.fitness() {
+            best = individual;
+        }
+    }
+
+    best
+}
+
+/// Sorts population from the fittest to the least fit individual.
+///
+/// The sort is stable - individuals with equal fitness (or with NaN
+/// fitness) keep their original relative order.
+pub fn sort_population_by_fitness<I>(population: &mut [I])
+where
+    I: Individual,
+{
+    population.sort_by(compare_fitness_descending);
+}
+
+fn compare_fitness_descending<I>(a: &I, b: &I) -> Ordering
+where
+    I: Individual,
+{
+    b.fitness()
+        .partial_cmp(&a.fitness())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Concatenates both populations and keeps only the `keep` fittest
+/// individuals, ordered from the fittest.
+pub fn merge_populations<I>(a: Vec<I>, b: Vec<I>, keep: usize) -> Vec<I>
+where
+    I: Individual,
+{
+    let mut population = a;
+    population.extend(b);
+
+    sort_population_by_fitness(&mut population);
+
+    population.truncate(keep);
+    population
+}
+
+/// Returns fitness of each individual divided by the number of individuals
+/// (including itself) whose chromosomes lie within `sigma` of its own, so
+/// that a crowd of similar individuals can't dominate the selection.
+pub fn shared_fitness<I>(population: &[I], sigma: f32) -> Vec<f32>
+where
+    I: Individual,
+{
+    population
+        .iter()
+        .map(|individual| {
+            let neighbours = population
+                .iter()
+                .filter(|other| individual.chromosome().distance(other.chromosome()) < sigma)
+                .count();
+
+            individual.fitness() / neighbours as f32
+        })
+        .collect()
+}
+
+/// Individual with its fitness replaced by the effective one (see
+/// `GeneticAlgorithm::effective_fitness()`), used to drive selection methods
+struct EffectiveFitness<'a, I> {
+    individual: &'a I,
+    fitness: f32,
+}
+
+impl<I> Individual for EffectiveFitness<'_, I>
+where
+    I: Individual,
+{
+    fn chromosome(&self) -> &Chromosome {
+        self.individual.chromosome()
+    }
+
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn create(_: Chromosome) -> Self {
+        panic!("not supported for EffectiveFitness")
+    }
+}
+
+/// Rebuilds a population out of (e.g. previously saved) chromosomes, so that
+/// evolution can be resumed instead of starting from scratch.
+pub fn population_from_chromosomes<I>(
+    chromosomes: Vec<Chromosome>,
+    build: impl Fn(Chromosome) -> I,
+) -> Vec<I>
+where
+    I: Individual,
+{
+    chromosomes.into_iter().map(build).collect()
+}
+
+/// Helpers for building populations, which are otherwise plain `Vec<I>`s
+pub struct Population;
+impl Population {
+    /// Builds the initial (generation-zero) population of `n` individuals,
+    /// each drawn by `factory`; evolution then proceeds by feeding it (and
+    /// each of the subsequent generations) into `GeneticAlgorithm::evolve()`.
+    pub fn random<I>(
+        n: usize,
+        rng: &mut dyn RngCore,
+        factory: impl Fn(&mut dyn RngCore) -> I,
+    ) -> Vec<I>
+    where
+        I: Individual,
+    {
+        (0..n).map(|_| factory(rng)).collect()
+    }
+}
+
+pub struct Island<S, C, M, I> {
+    ga: GeneticAlgorithm<S, C, M>,
+    population: Vec<I>,
+}
+impl<S, C, M, I> Island<S, C, M, I> {
+    pub fn new(ga: GeneticAlgorithm<S, C, M>, population: Vec<I>) -> Self {
+        Self { ga, population }
+    }
+
+    pub fn population(&self) -> &[I] {
+        &self.population
+    }
+
+    fn evolve(&mut self, rng: &mut dyn RngCore, generations: usize)
+    where
+        S: SelectionMethod,
+        C: CrossoverMethod,
+        M: MutationMethod,
+        I: Individual,
+    {
+        for _ in 0..generations {
+            self.population = self.ga.evolve(rng, &self.population);
+        }
+    }
+}
+
+/// Evolves a few independent populations (islands), periodically letting
+/// the fittest individuals of each island migrate to the next one in a
+/// ring.
+pub struct IslandModel<S, C, M, I> {
+    islands: Vec<Island<S, C, M, I>>,
+
+    /// Number of generations each island evolves between migrations
+    epoch: usize,
+
+    /// Number of the fittest individuals sent to the neighbouring island,
+    /// replacing its weakest ones
+    migrants: usize,
+}
+impl<S, C, M, I> IslandModel<S, C, M, I>
+where
+    S: SelectionMethod,
+    C: CrossoverMethod,
+    M: MutationMethod,
+    I: Individual + Clone,
+{
+    pub fn new(islands: Vec<Island<S, C, M, I>>, epoch: usize, migrants: usize) -> Self {
+        assert!(!islands.is_empty());
+
+        for island in &islands {
+            assert!(migrants <= island.population.len());
+        }
+
+        Self {
+            islands,
+            epoch,
+            migrants,
+        }
+    }
+
+    pub fn islands(&self) -> &[Island<S, C, M, I>] {
+        &self.islands
+    }
+
+    /// Returns the fittest individual of each island
+    pub fn bests(&self) -> Vec<&I> {
+        self.islands
+            .iter()
+            .map(|island| best_individual(&island.population))
+            .collect()
+    }
+
+    pub fn step_epoch(&mut self, rng: &mut dyn RngCore) {
+        for island in &mut self.islands {
+            island.evolve(rng, self.epoch);
+        }
+
+        self.migrate();
+    }
+
+    /// Like `step_epoch()`, but each island gets evolved using its own RNG
+    /// (`rngs[n]` for `n`-th island), making the outcome independent of the
+    /// order islands get evolved in - see `par_step_epoch()`.
+    pub fn step_epoch_with_rngs<R>(&mut self, rngs: &mut [R])
+    where
+        R: RngCore,
+    {
+        assert_eq!(rngs.len(), self.islands.len());
+
+        for (island, rng) in self.islands.iter_mut().zip(rngs) {
+            island.evolve(rng, self.epoch);
+        }
+
+        self.migrate();
+    }
+
+    /// Parallel version of `step_epoch_with_rngs()`, evolving each island
+    /// on its own rayon task; migration happens afterwards in a fixed order,
+    /// so the outcome doesn't depend on thread scheduling.
+    #[cfg(feature = "rayon")]
+    pub fn par_step_epoch<R>(&mut self, rngs: &mut [R])
+    where
+        S: Send,
+        C: Send,
+        M: Send,
+        I: Send,
+        R: RngCore + Send,
+    {
+        use rayon::prelude::*;
+
+        assert_eq!(rngs.len(), self.islands.len());
+
+        let epoch = self.epoch;
+
+        self.islands
+            .par_iter_mut()
+            .zip(rngs.par_iter_mut())
+            .for_each(|(island, rng)| island.evolve(rng, epoch));
+
+        self.migrate();
+    }
+
+    fn migrate(&mut self) {
+        let emigrants: Vec<Vec<I>> = self
+            .islands
+            .iter()
+            .map(|island| merge_populations(island.population.clone(), Vec::new(), self.migrants))
+            .collect();
+
+        let len = self.islands.len();
+
+        for (idx, emigrants) in emigrants.into_iter().enumerate() {
+            let island = &mut self.islands[(idx + 1) % len];
+            let keep = island.population.len() - self.migrants;
+
+            island.population =
+                merge_populations(std::mem::take(&mut island.population), Vec::new(), keep);
+
+            island.population.extend(emigrants);
+        }
+    }
+}
+
+#[cfg(test)]
+mod genetic_algorithm {
+    use rand_chacha::ChaCha8Rng;
+
+    use self::selection::TestIndividual;
+
+    use super::*;
+
+    mod selection {
+        use super::*;
+        use rand_chacha::ChaCha8Rng;
+        use std::collections::BTreeMap;
+
+        #[cfg(test)]
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum TestIndividual {
+            WithChromosome { chromosome: Chromosome },
+            WithFitness { fitness: f32 },
+        }
+
+        #[cfg(test)]
+        impl TestIndividual {
+            pub fn new(fitness: f32) -> Self {
+                Self::WithFitness { fitness }
+            }
+        }
+
+        #[cfg(test)]
         impl Individual for TestIndividual {
             fn create(chromosome: Chromosome) -> Self {
                 Self::WithChromosome { chromosome }
             }
-            fn chromosome(&self) -> &Chromosome {
-                match self {
-                    Self::WithChromosome { chromosome } => chromosome,
-                    Self::WithFitness { .. } => {
-                        panic!("Not supported for TestIndividual::WithFitness")
-                    }
+            fn chromosome(&self) -> &Chromosome {
+                match self {
+                    Self::WithChromosome { chromosome } => chromosome,
+                    Self::WithFitness { .. } => {
+                        panic!("Not supported for TestIndividual::WithFitness")
+                    }
+                }
+            }
+            fn fitness(&self) -> f32 {
+                match self {
+                    Self::WithChromosome { chromosome } => chromosome.iter().sum(),
+                    Self::WithFitness { fitness } => *fitness,
+                }
+            }
+        }
+
+        #[test]
+        fn roulette_selection() {
+            let method = RouletteWheelSelection::new();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population = vec![
+                TestIndividual::new(2.0),
+                TestIndividual::new(1.0),
+                TestIndividual::new(4.0),
+                TestIndividual::new(3.0),
+            ];
+
+            let actual_histogram: BTreeMap<i32, _> = (0..1000)
+                .map(|_| method.select(&mut rng, &population))
+                .fold(Default::default(), |mut histogram, individual| {
+                    *histogram.entry(individual.fitness() as _).or_default() += 1;
+
+                    histogram
+                });
+
+            // Weights are `f64`, which samples differently than `f32` would
+            let expected_histogram = maplit::btreemap! {
+                1 => 98,
+                2 => 191,
+                3 => 280,
+                4 => 431,
+            };
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+
+        #[test]
+        fn roulette_selection_probabilities() {
+            let method = RouletteWheelSelection::new();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population = vec![
+                TestIndividual::new(2.0),
+                TestIndividual::new(1.0),
+                TestIndividual::new(4.0),
+                TestIndividual::new(0.001),
+                TestIndividual::new(3.0),
+            ];
+
+            assert_probabilities(&method, &mut rng, &population, &[2.0, 1.0, 4.0, 0.001, 3.0]);
+        }
+
+        #[test]
+        fn roulette_selection_with_fitness_floor() {
+            let method = RouletteWheelSelection::new().with_fitness_floor(0.1);
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population = vec![
+                TestIndividual::new(0.0),
+                TestIndividual::new(0.0),
+                TestIndividual::new(0.3),
+                TestIndividual::new(0.0),
+            ];
+
+            assert_probabilities(&method, &mut rng, &population, &[0.1, 0.1, 0.4, 0.1]);
+        }
+
+        /// Checks that each individual gets selected proportionally to its
+        /// weight in `weights`
+        fn assert_probabilities(
+            method: &RouletteWheelSelection,
+            rng: &mut dyn RngCore,
+            population: &[TestIndividual],
+            weights: &[f64],
+        ) {
+            const SAMPLES: usize = 200_000;
+
+            let mut counts = vec![0; population.len()];
+
+            for _ in 0..SAMPLES {
+                let selected = method.select(rng, population);
+                let idx = population
+                    .iter()
+                    .position(|individual| std::ptr::eq(individual, selected))
+                    .unwrap();
+
+                counts[idx] += 1;
+            }
+
+            let total: f64 = weights.iter().sum();
+
+            for (count, weight) in counts.into_iter().zip(weights) {
+                approx::assert_abs_diff_eq!(
+                    count as f64 / SAMPLES as f64,
+                    weight / total,
+                    epsilon = 0.003,
+                );
+            }
+        }
+
+        #[test]
+        fn best_selection() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population = vec![
+                TestIndividual::new(2.0),
+                TestIndividual::new(4.0),
+                TestIndividual::new(1.0),
+                TestIndividual::new(4.0),
+            ];
+
+            for _ in 0..100 {
+                let selected = BestSelection::new().select(&mut rng, &population);
+
+                assert!(std::ptr::eq(selected, &population[1]));
+            }
+        }
+
+        #[test]
+        fn boxed() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population = vec![
+                TestIndividual::new(0.0),
+                TestIndividual::new(0.0),
+                TestIndividual::new(3.0),
+                TestIndividual::new(0.0),
+            ];
+
+            let selection_methods: Vec<Box<dyn DynSelectionMethod>> = vec![
+                Box::new(RouletteWheelSelection::new()),
+                Box::new(TournamentSelection::new(4).without_replacement(true)),
+            ];
+
+            for selection_method in selection_methods {
+                let selection_method = BoxedSelection(selection_method);
+
+                for _ in 0..10 {
+                    let selected = selection_method.select(&mut rng, &population);
+
+                    assert_eq!(selected.fitness(), 3.0);
+                }
+            }
+        }
+
+        #[test]
+        fn sigma_scaling() {
+            let fitness = [1.0, 1.0, 1.0, 100.0];
+
+            let best_selections = |fitness: &[f32]| {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                let population: Vec<_> = fitness
+                    .iter()
+                    .map(|&fitness| TestIndividual::new(fitness))
+                    .collect();
+
+                (0..1000)
+                    .filter(|_| {
+                        let selected = RouletteWheelSelection::new().select(&mut rng, &population);
+                        std::ptr::eq(selected, &population[3])
+                    })
+                    .count()
+            };
+
+            let scaled = SigmaScaling::new().scale(&fitness);
+
+            approx::assert_relative_eq!(scaled[0], 0.7113249);
+            approx::assert_relative_eq!(scaled[3], 1.8660254);
+
+            assert!(best_selections(&fitness) > 950);
+            assert!(best_selections(&scaled) < 550);
+        }
+
+        #[test]
+        fn tournament_without_replacement() {
+            let method = TournamentSelection::new(5).without_replacement(true);
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            for _ in 0..100 {
+                let mut contestants = method.contestants(&mut rng, 5);
+
+                contestants.sort_unstable();
+                contestants.dedup();
+
+                assert_eq!(contestants.len(), 5);
+            }
+
+            let population = vec![
+                TestIndividual::new(2.0),
+                TestIndividual::new(1.0),
+                TestIndividual::new(4.0),
+                TestIndividual::new(3.0),
+                TestIndividual::new(0.5),
+            ];
+
+            // Everyone competes, so the fittest always wins
+            assert_eq!(method.select(&mut rng, &population).fitness(), 4.0);
+        }
+    }
+
+    mod chromosomes {
+        use super::*;
+
+        impl PartialEq for Chromosome {
+            fn eq(&self, other: &Self) -> bool {
+                approx::relative_eq!(self.genes.as_slice(), other.genes.as_slice(),)
+            }
+        }
+
+        fn chromosome() -> Chromosome {
+            Chromosome {
+                genes: vec![3.0, 1.0, 2.0],
+            }
+        }
+
+        #[test]
+        fn len() {
+            assert_eq!(chromosome().len(), 3);
+        }
+
+        #[test]
+        fn iter() {
+            let chromosome = chromosome();
+            let genes: Vec<_> = chromosome.iter().collect();
+
+            assert_eq!(genes.len(), 3);
+            assert_eq!(genes[0], &3.0);
+            assert_eq!(genes[1], &1.0);
+            assert_eq!(genes[2], &2.0);
+        }
+
+        #[test]
+        fn iter_mut() {
+            let mut chromosome = chromosome();
+
+            chromosome.iter_mut().for_each(|gene| {
+                *gene *= 10.0;
+            });
+
+            let genes: Vec<_> = chromosome.iter().collect();
+
+            assert_eq!(genes.len(), 3);
+            assert_eq!(genes[0], &30.0);
+            assert_eq!(genes[1], &10.0);
+            assert_eq!(genes[2], &20.0);
+        }
+
+        #[test]
+        fn index() {
+            let chromosome = chromosome();
+
+            assert_eq!(chromosome[0], 3.0);
+            assert_eq!(chromosome[1], 1.0);
+            assert_eq!(chromosome[2], 2.0);
+        }
+
+        #[test]
+        fn normalize() {
+            let mut chromosome: Chromosome = vec![3.0, 0.0, -4.0].into_iter().collect();
+
+            approx::assert_relative_eq!(chromosome.norm(), 5.0);
+
+            chromosome.normalize();
+
+            approx::assert_relative_eq!(chromosome.norm(), 1.0);
+            assert_eq!(chromosome[0], 0.6);
+            assert_eq!(chromosome[2], -0.8);
+        }
+
+        #[test]
+        fn normalize_zero() {
+            let mut chromosome: Chromosome = vec![0.0, 0.0].into_iter().collect();
+
+            chromosome.normalize();
+
+            assert_eq!(chromosome[0], 0.0);
+            assert_eq!(chromosome[1], 0.0);
+        }
+
+        #[test]
+        fn from_iter() {
+            let chromosome: Chromosome = vec![3.0, 1.0, 2.0].into_iter().collect();
+
+            assert_eq!(chromosome[0], 3.0);
+            assert_eq!(chromosome[1], 1.0);
+            assert_eq!(chromosome[2], 2.0);
+        }
+    }
+
+    mod crossover {
+        use super::*;
+        use rand_chacha::ChaCha8Rng;
+        use std::collections::HashSet;
+
+        #[test]
+        fn uniform() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
+            let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
+
+            let child = UniformCrossover::new().crossover(&mut rng, &parent_a, &parent_b);
+
+            let diff_a = child.iter().zip(parent_a).filter(|(c, p)| *c != p).count();
+            let diff_b = child.iter().zip(parent_b).filter(|(c, p)| *c != p).count();
+
+            assert_eq!(diff_a, 49);
+            assert_eq!(diff_b, 51);
+        }
+
+        #[test]
+        fn uniform_with_bias() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let parent_a: Chromosome = (1..=1000).map(|n| n as f32).collect();
+            let parent_b: Chromosome = (1..=1000).map(|n| -n as f32).collect();
+
+            let child = UniformCrossover::new()
+                .with_bias(0.9)
+                .crossover(&mut rng, &parent_a, &parent_b);
+
+            let from_a = child.iter().zip(parent_a).filter(|(c, p)| *c == p).count();
+
+            assert!((870..=930).contains(&from_a), "from_a = {}", from_a);
+        }
+
+        #[test]
+        fn layer_aware() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let layers = vec![12, 20, 6];
+            let parent_a: Chromosome = (1..=38).map(|n| n as f32).collect();
+            let parent_b: Chromosome = (1..=38).map(|n| -n as f32).collect();
+
+            let mut seen = HashSet::new();
+
+            for _ in 0..20 {
+                let child = LayerAwareCrossover::new(layers.clone())
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                let mask = crossover_source_mask(&child, &parent_a, &parent_b);
+                let mut offset = 0;
+
+                let sources: Vec<_> = layers
+                    .iter()
+                    .map(|&layer| {
+                        let source = mask[offset];
+                        assert!(mask[offset..offset + layer].iter().all(|s| *s == source));
+
+                        offset += layer;
+                        source
+                    })
+                    .collect();
+
+                seen.insert(sources);
+            }
+
+            // Layers don't all come from the same parent
+            assert!(seen.len() > 2);
+        }
+
+        #[test]
+        fn single_point() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
+            let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
+
+            for _ in 0..10 {
+                let child = SinglePointCrossover::new().crossover(&mut rng, &parent_a, &parent_b);
+                let mask = crossover_source_mask(&child, &parent_a, &parent_b);
+
+                let point = mask
+                    .iter()
+                    .take_while(|&&from_a| from_a == Some(true))
+                    .count();
+
+                assert!(mask[point..].iter().all(|&from_a| from_a == Some(false)));
+            }
+        }
+
+        #[test]
+        fn disruption() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
+            let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
+
+            let uniform =
+                crossover_disruption(&UniformCrossover::new(), &mut rng, &parent_a, &parent_b, 50);
+
+            let single_point = crossover_disruption(
+                &SinglePointCrossover::new(),
+                &mut rng,
+                &parent_a,
+                &parent_b,
+                50,
+            );
+
+            // Uniform crossover switches parents at every other pair,
+            // single-point one at most once per child
+            approx::assert_relative_eq!(uniform, 0.5, epsilon = 0.05);
+            assert!(single_point <= 1.0 / 99.0);
+            assert!(uniform > single_point);
+        }
+
+        #[test]
+        fn source_mask() {
+            let parent_a: Chromosome = vec![1.0, 2.0, 3.0].into_iter().collect();
+            let parent_b: Chromosome = vec![1.0, 5.0, 6.0].into_iter().collect();
+            let child: Chromosome = vec![1.0, 5.0, 3.0].into_iter().collect();
+
+            assert_eq!(
+                crossover_source_mask(&child, &parent_a, &parent_b),
+                vec![None, Some(false), Some(true)]
+            );
+        }
+    }
+
+    mod mutation {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        fn actual(chance: f32, coeff: f32) -> Vec<f32> {
+            let mut child = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            GaussianMutation::new(chance, coeff).mutate(&mut rng, &mut child);
+
+            child.into_iter().collect()
+        }
+
+        mod given_zero_change {
+            fn actual(coeff: f32) -> Vec<f32> {
+                super::actual(0.0, coeff)
+            }
+
+            mod and_zero_coefficient {
+                use super::*;
+
+                #[test]
+                fn does_not_change_the_original_chromosome() {
+                    let actual = actual(0.0);
+                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
+                }
+            }
+            mod and_nonzero_coefficient {
+                use super::*;
+
+                #[test]
+                fn does_not_change_the_original_chromosome() {
+                    let actual = actual(0.5);
+                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
+                }
+            }
+        }
+
+        mod given_fifty_fifty_change {
+            fn actual(coeff: f32) -> Vec<f32> {
+                super::actual(0.5, coeff)
+            }
+
+            mod and_zero_coeddicient {
+                use super::*;
+                #[test]
+                fn does_not_change_the_original_chromosome() {
+                    let actual = actual(0.0);
+                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
+                }
+            }
+
+            mod and_nonzero_coefficient {
+                use super::*;
+
+                #[test]
+                fn slightly_changes_the_original_chromosome() {
+                    let actual = actual(0.5);
+                    let expected = vec![1.0, 1.7756249, 3.0, 4.1596804, 5.0];
+
+                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
+                }
+            }
+        }
+
+        mod given_max_chance {
+            fn actual(coeff: f32) -> Vec<f32> {
+                super::actual(1.0, coeff)
+            }
+
+            mod and_zero_coeddicient {
+                use super::*;
+
+                #[test]
+                fn does_not_change_the_original_chromosome() {
+                    let actual = actual(0.0);
+                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
+                }
+            }
+
+            mod and_nonzero_coefficient {
+                use super::*;
+
+                #[test]
+                fn entirely_changes_the_original_chromosome() {
+                    let actual = actual(0.5);
+                    let expected = vec![1.4545316, 2.1162078, 2.7756248, 3.9505124, 4.638691];
+
+                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
+                }
+            }
+        }
+
+        #[test]
+        fn role_aware() {
+            let biases: Vec<_> = (0..1000).map(|idx| idx % 4 == 0).collect();
+            let mut child: Chromosome = (0..1000).map(|_| 0.0).collect();
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            RoleAwareMutation::new(
+                GaussianMutation::new(1.0, 0.1),
+                GaussianMutation::new(1.0, 1.0),
+                biases.clone(),
+            )
+            .mutate(&mut rng, &mut child);
+
+            let magnitude = |role: bool| {
+                let genes: Vec<_> = child
+                    .iter()
+                    .zip(&biases)
+                    .filter(|(_, &is_bias)| is_bias == role)
+                    .map(|(gene, _)| gene.abs())
+                    .collect();
+
+                genes.iter().sum::<f32>() / genes.len() as f32
+            };
+
+            // Both average out at around half of their coefficient
+            approx::assert_relative_eq!(magnitude(true), 0.05, epsilon = 0.01);
+            approx::assert_relative_eq!(magnitude(false), 0.5, epsilon = 0.05);
+        }
+
+        #[test]
+        fn masked_only_changes_unfrozen_genes() {
+            let original: Chromosome = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0].into_iter().collect();
+            let mut child = original.clone();
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            MaskedMutation::new(
+                GaussianMutation::new(1.0, 0.5),
+                vec![true, false, true, false, true, false],
+            )
+            .mutate(&mut rng, &mut child);
+
+            for (idx, (actual, expected)) in child.iter().zip(original.iter()).enumerate() {
+                if idx % 2 == 0 {
+                    assert_eq!(actual, expected);
+                } else {
+                    assert_ne!(actual, expected);
+                }
+            }
+        }
+
+        #[test]
+        fn chain() {
+            struct ClampMutation;
+
+            impl MutationMethod for ClampMutation {
+                fn mutate(&self, _: &mut dyn RngCore, child: &mut Chromosome) {
+                    for gene in child.iter_mut() {
+                        *gene = gene.clamp(-1.0, 1.0);
+                    }
+                }
+            }
+
+            let original: Chromosome = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6].into_iter().collect();
+            let mut child = original.clone();
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            ChainMutation::new()
+                .then(GaussianMutation::new(1.0, 2.0))
+                .then(ClampMutation)
+                .mutate(&mut rng, &mut child);
+
+            for (actual, expected) in child.iter().zip(original.iter()) {
+                assert_ne!(actual, expected);
+                assert!((-1.0..=1.0).contains(actual));
+            }
+
+            assert!(child.iter().any(|gene| gene.abs() == 1.0));
+        }
+    }
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        let chromosome = genes.iter().cloned().collect();
+
+        TestIndividual::create(chromosome)
+    }
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let mut population = vec![
+            individual(&[0.0, 0.0, 0.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ];
+
+        for _ in 0..10 {
+            population = ga.evolve(&mut rng, &population);
+        }
+
+        let expected_population = vec![
+            individual(&[1.2398922, 1.4658892, 4.431967]),
+            individual(&[1.3136789, 1.1207112, 4.1562424]),
+            individual(&[0.86144316, 1.3362668, 4.1562424]),
+            individual(&[0.93749946, 1.3362668, 4.354477]),
+        ];
+
+        assert_eq!(population, expected_population);
+    }
+
+    mod population_diversity {
+        use super::*;
+
+        #[test]
+        fn identical_chromosomes() {
+            let population = vec![
+                individual(&[1.0, 2.0, 3.0]),
+                individual(&[1.0, 2.0, 3.0]),
+                individual(&[1.0, 2.0, 3.0]),
+            ];
+
+            approx::assert_relative_eq!(population_diversity(&population), 0.0);
+        }
+
+        #[test]
+        fn spread_out_chromosomes() {
+            let population = vec![
+                individual(&[0.0, 0.0]),
+                individual(&[3.0, 4.0]),
+                individual(&[0.0, 4.0]),
+            ];
+
+            // Pairwise distances are 5.0, 4.0 and 3.0
+            approx::assert_relative_eq!(population_diversity(&population), 4.0);
+        }
+    }
+
+    mod is_population_degenerate {
+        use super::*;
+
+        #[test]
+        fn identical_chromosomes() {
+            let population = vec![
+                individual(&[1.0, 2.0, 3.0]),
+                individual(&[1.0, 2.0, 3.0]),
+                individual(&[1.0, 2.0, 3.0]),
+            ];
+
+            assert!(crate::is_population_degenerate(&population, 0.0));
+        }
+
+        #[test]
+        fn nearly_identical_chromosomes() {
+            let population = vec![
+                individual(&[1.0, 2.0]),
+                individual(&[1.0, 2.001]),
+                individual(&[1.001, 2.0]),
+            ];
+
+            assert!(crate::is_population_degenerate(&population, 0.01));
+            assert!(!crate::is_population_degenerate(&population, 0.0001));
+        }
+
+        #[test]
+        fn spread_out_chromosomes() {
+            let population = vec![
+                individual(&[0.0, 0.0]),
+                individual(&[3.0, 4.0]),
+                individual(&[0.0, 4.0]),
+            ];
+
+            assert!(!crate::is_population_degenerate(&population, 1.0));
+        }
+    }
+
+    #[test]
+    fn run() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.1),
+        )
+        .with_elitism(1);
+
+        let population = vec![
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[2.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ];
+
+        let (population, statistics) = ga.run(&mut rng, population, 5);
+
+        assert_eq!(population.len(), 4);
+        assert_eq!(statistics.len(), 5);
+
+        for stats in statistics.windows(2) {
+            assert!(stats[1].max_fitness() >= stats[0].max_fitness());
+        }
+    }
+
+    #[test]
+    fn run_with_callback() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.5),
+        )
+        .with_elitism(1);
+
+        let population = vec![
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[2.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ];
+
+        let (population, statistics) =
+            ga.run_with_callback(&mut rng, population, 1000, |_, statistics| {
+                if statistics.max_fitness() > 10.0 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            });
+
+        assert!(statistics.len() < 1000);
+        assert!(statistics.last().unwrap().max_fitness() > 10.0);
+        assert!(Statistics::new(&population).max_fitness() > 10.0);
+    }
+
+    #[test]
+    fn full_regularization() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.5),
+        )
+        .with_regularization(1.0);
+
+        let population = vec![
+            individual(&[0.0, 1.0, 2.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[2.0, 4.0, 0.0]),
+            individual(&[1.0, 2.0, 5.0]),
+        ];
+
+        let expected = individual(&[1.0, 2.0, 2.0]);
+
+        for child in ga.evolve(&mut rng, &population) {
+            assert_eq!(child, expected);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_round_trip() {
+        let ga = GeneticAlgorithm::new(
+            TournamentSelection::new(2),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.5),
+        )
+        .with_elitism(1)
+        .with_regularization(0.25);
+
+        let json = serde_json::to_string(&ga.config()).unwrap();
+        let config: GaConfig<TournamentSelection, UniformCrossover, GaussianMutation> =
+            serde_json::from_str(&json).unwrap();
+
+        assert_eq!(config.elitism, 1);
+        approx::assert_relative_eq!(config.regularization, 0.25);
+
+        let rebuilt = GeneticAlgorithm::from(config);
+
+        let population = vec![
+            individual(&[0.0, 1.0, 2.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[2.0, 4.0, 0.0]),
+            individual(&[1.0, 2.0, 5.0]),
+        ];
+
+        let expected = ga.evolve(&mut ChaCha8Rng::from_seed(Default::default()), &population);
+        let actual = rebuilt.evolve(&mut ChaCha8Rng::from_seed(Default::default()), &population);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(feature = "timing")]
+    #[test]
+    fn run_timed() {
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.1),
+        );
+
+        let population = vec![
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ];
+
+        let (population, statistics, timings) = ga.run_timed(
+            &mut ChaCha8Rng::from_seed(Default::default()),
+            population,
+            7,
+        );
+
+        assert_eq!(statistics.len(), 7);
+        assert_eq!(timings.len(), 7);
+
+        // Timing must not affect the results
+        let (expected, _) = ga.run(
+            &mut ChaCha8Rng::from_seed(Default::default()),
+            vec![
+                individual(&[1.0, 1.0, 1.0]),
+                individual(&[1.0, 2.0, 1.0]),
+                individual(&[1.0, 2.0, 4.0]),
+            ],
+            7,
+        );
+
+        assert_eq!(population, expected);
+    }
+
+    #[test]
+    fn population_random() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = Population::random(5, &mut rng, |rng| {
+            individual(&[rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)])
+        });
+
+        assert_eq!(population.len(), 5);
+        assert!(population[1..].iter().all(|other| *other != population[0]));
+
+        let ga = GeneticAlgorithm::new(
+            TournamentSelection::new(2),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.1),
+        );
+
+        assert_eq!(ga.evolve(&mut rng, &population).len(), 5);
+    }
+
+    #[test]
+    fn population_from_chromosomes() {
+        let population = vec![
+            individual(&[0.0, 1.0, 2.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[2.0, 4.0, 0.0]),
+        ];
+
+        let chromosomes: Vec<_> = population
+            .iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect();
+
+        let restored = super::population_from_chromosomes(chromosomes, TestIndividual::create);
+
+        assert_eq!(restored, population);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn population_from_chromosomes_serde() {
+        let population = vec![
+            individual(&[0.0, 1.0, 2.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[2.0, 4.0, 0.0]),
+        ];
+
+        let chromosomes: Vec<_> = population
+            .iter()
+            .map(|individual| individual.chromosome())
+            .collect();
+
+        let json = serde_json::to_string(&chromosomes).unwrap();
+
+        let restored = super::population_from_chromosomes(
+            serde_json::from_str(&json).unwrap(),
+            TestIndividual::create,
+        );
+
+        assert_eq!(restored, population);
+    }
+
+    #[test]
+    fn gene_tracker() {
+        let mut tracker = GeneTracker::new(3);
+
+        for chromosome in [
+            [9.0, 5.0, 9.0],
+            [1.0, 2.0, 3.0],
+            [2.0, 2.0, 1.0],
+            [3.0, 2.0, 2.0],
+        ] {
+            tracker.push(chromosome.into_iter().collect());
+        }
+
+        let variances = tracker.variances();
+
+        approx::assert_relative_eq!(variances.as_slice(), [2.0 / 3.0, 0.0, 2.0 / 3.0].as_slice());
+        assert_eq!(tracker.fixated(0.01), vec![1]);
+    }
+
+    #[test]
+    fn regression_monitor() {
+        let mut monitor = RegressionMonitor::new();
+
+        let regressed: Vec<_> = [1.0, 3.0, 3.0, 2.0, 4.0]
+            .into_iter()
+            .map(|fitness| monitor.push(&Statistics::new(&[individual(&[fitness])])))
+            .collect();
+
+        assert_eq!(regressed, vec![false, false, false, true, false]);
+        assert_eq!(monitor.regressions(), &[3]);
+    }
+
+    #[test]
+    fn fitness_cache() {
+        let mut cache = FitnessCache::new();
+        let mut evaluations = 0;
+
+        let chromosomes: Vec<Chromosome> = vec![
+            vec![1.0, 2.0].into_iter().collect(),
+            vec![3.0, 4.0].into_iter().collect(),
+            vec![1.0, 2.0].into_iter().collect(),
+        ];
+
+        let fitness: Vec<_> = chromosomes
+            .iter()
+            .map(|chromosome| {
+                cache.get_or_evaluate(chromosome, |chromosome| {
+                    evaluations += 1;
+                    chromosome.iter().sum()
+                })
+            })
+            .collect();
+
+        assert_eq!(fitness, vec![3.0, 7.0, 3.0]);
+        assert_eq!(evaluations, 2);
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn merge_populations() {
+        let a = vec![
+            TestIndividual::new(1.0),
+            TestIndividual::new(7.0),
+            TestIndividual::new(3.0),
+        ];
+
+        let b = vec![
+            TestIndividual::new(5.0),
+            TestIndividual::new(2.0),
+            TestIndividual::new(6.0),
+        ];
+
+        let actual = super::merge_populations(a, b, 3);
+
+        let expected = vec![
+            TestIndividual::new(7.0),
+            TestIndividual::new(6.0),
+            TestIndividual::new(5.0),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn island_model() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let ga = || {
+            GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.0, 0.0),
+            )
+            .with_elitism(1)
+        };
+
+        let strong = individual(&[10.0, 10.0, 10.0]);
+        let weak = || individual(&[1.0, 1.0, 1.0]);
+
+        let mut model = IslandModel::new(
+            vec![
+                Island::new(ga(), vec![strong.clone(), weak(), weak(), weak()]),
+                Island::new(ga(), vec![weak(), weak(), weak(), weak()]),
+            ],
+            1,
+            1,
+        );
+
+        assert_eq!(model.bests()[1], &weak());
+
+        model.step_epoch(&mut rng);
+
+        assert_eq!(model.bests()[0], &strong);
+        assert_eq!(model.bests()[1], &strong);
+        assert_eq!(model.islands()[1].population().len(), 4);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_island_model() {
+        let model = || {
+            let ga = || {
+                GeneticAlgorithm::new(
+                    RouletteWheelSelection::new(),
+                    UniformCrossover::new(),
+                    GaussianMutation::new(0.5, 0.5),
+                )
+            };
+
+            let islands = (0..4)
+                .map(|n| {
+                    let population = (0..10)
+                        .map(|m| individual(&[n as f32, m as f32, 1.0]))
+                        .collect();
+
+                    Island::new(ga(), population)
+                })
+                .collect();
+
+            IslandModel::new(islands, 3, 2)
+        };
+
+        let rngs = || -> Vec<_> { (0..4).map(ChaCha8Rng::seed_from_u64).collect() };
+
+        let mut sequential = model();
+        let mut parallel = model();
+
+        let mut sequential_rngs = rngs();
+        let mut parallel_rngs = rngs();
+
+        for _ in 0..5 {
+            sequential.step_epoch_with_rngs(&mut sequential_rngs);
+            parallel.par_step_epoch(&mut parallel_rngs);
+        }
+
+        assert_eq!(parallel.bests(), sequential.bests());
+    }
+
+    mod preserve_best {
+        use super::*;
+
+        fn max_fitnesses(preserve_best: bool) -> Vec<f32> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(1.0, 0.5),
+            )
+            .with_preserve_best(preserve_best);
+
+            let mut population = vec![
+                individual(&[1.0, 1.0, 1.0]),
+                individual(&[1.0, 2.0, 1.0]),
+                individual(&[2.0, 2.0, 1.0]),
+                individual(&[1.0, 2.0, 4.0]),
+            ];
+
+            (0..50)
+                .map(|_| {
+                    population = ga.evolve(&mut rng, &population);
+                    Statistics::new(&population).max_fitness()
+                })
+                .collect()
+        }
+
+        #[test]
+        fn enabled() {
+            for fitnesses in max_fitnesses(true).windows(2) {
+                assert!(fitnesses[1] >= fitnesses[0]);
+            }
+        }
+
+        #[test]
+        fn disabled() {
+            assert!(max_fitnesses(false)
+                .windows(2)
+                .any(|fitnesses| fitnesses[1] < fitnesses[0]));
+        }
+    }
+
+    #[test]
+    fn shared_fitness() {
+        let population = vec![
+            individual(&[3.0, 3.0, 3.0]),
+            individual(&[3.0, 3.01, 2.99]),
+            individual(&[3.01, 2.99, 3.0]),
+            individual(&[9.0, 0.0, 0.0]),
+        ];
+
+        let actual = super::shared_fitness(&population, 0.1);
+
+        approx::assert_relative_eq!(actual.as_slice(), [3.0, 3.0, 3.0, 9.0].as_slice());
+    }
+
+    mod self_crossover_retries {
+        use super::*;
+        use std::cell::Cell;
+
+        /// Counts crossovers of an individual with itself
+        #[derive(Default)]
+        struct SelfCrossoverCounter(Cell<usize>);
+
+        impl CrossoverMethod for &SelfCrossoverCounter {
+            fn crossover(
+                &self,
+                _: &mut dyn RngCore,
+                parent_a: &Chromosome,
+                parent_b: &Chromosome,
+            ) -> Chromosome {
+                if std::ptr::eq(parent_a, parent_b) {
+                    self.0.set(self.0.get() + 1);
                 }
+
+                parent_a.clone()
             }
-            fn fitness(&self) -> f32 {
-                match self {
-                    Self::WithChromosome { chromosome } => chromosome.iter().sum(),
-                    Self::WithFitness { fitness } => *fitness,
+        }
+
+        fn self_crossovers(retries: usize) -> usize {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let counter = SelfCrossoverCounter::default();
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                &counter,
+                GaussianMutation::new(0.0, 0.0),
+            )
+            .with_self_crossover_retries(retries);
+
+            let population = vec![
+                individual(&[10.0]),
+                individual(&[1.0]),
+                individual(&[1.0]),
+                individual(&[1.0]),
+            ];
+
+            for _ in 0..100 {
+                ga.evolve(&mut rng, &population);
+            }
+
+            counter.0.get()
+        }
+
+        #[test]
+        fn disabled() {
+            assert!(self_crossovers(0) > 100);
+        }
+
+        #[test]
+        fn enabled() {
+            assert!(self_crossovers(20) < 10);
+        }
+    }
+
+    #[test]
+    fn try_evolve_empty_population() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection::new(),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let population: Vec<TestIndividual> = Vec::new();
+
+        assert_eq!(
+            ga.try_evolve(&mut rng, &population),
+            Err(GaError::EmptyPopulation)
+        );
+    }
+
+    #[test]
+    fn sort_population_by_fitness() {
+        let mut population = vec![
+            individual(&[1.0, 1.0]),
+            individual(&[5.0, 0.0]),
+            individual(&[0.5, 0.0]),
+            individual(&[2.0, 0.0]),
+            individual(&[3.0, 2.0]),
+        ];
+
+        super::sort_population_by_fitness(&mut population);
+
+        let expected = vec![
+            individual(&[5.0, 0.0]),
+            individual(&[3.0, 2.0]),
+            individual(&[1.0, 1.0]),
+            individual(&[2.0, 0.0]),
+            individual(&[0.5, 0.0]),
+        ];
+
+        assert_eq!(population, expected);
+    }
+
+    mod elite_mutation {
+        use super::*;
+
+        fn elites(elite_mutation: Option<GaussianMutation>) -> Vec<TestIndividual> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.5, 0.5),
+            )
+            .with_elitism(2);
+
+            if let Some(elite_mutation) = elite_mutation {
+                ga = ga.with_elite_mutation(elite_mutation);
+            }
+
+            let population = vec![
+                individual(&[1.0, 1.0, 1.0]),
+                individual(&[4.0, 2.0, 1.0]),
+                individual(&[2.0, 2.0, 1.0]),
+                individual(&[1.0, 2.0, 4.0]),
+            ];
+
+            let mut population = ga.evolve(&mut rng, &population);
+            population.truncate(2);
+            population
+        }
+
+        fn expected() -> Vec<TestIndividual> {
+            vec![individual(&[4.0, 2.0, 1.0]), individual(&[1.0, 2.0, 4.0])]
+        }
+
+        #[test]
+        fn disabled() {
+            for (actual, expected) in elites(None).iter().zip(expected()) {
+                assert!(actual.chromosome().iter().eq(expected.chromosome().iter()));
+            }
+        }
+
+        #[test]
+        fn enabled() {
+            let actual = elites(Some(GaussianMutation::new(1.0, 0.01)));
+
+            for (actual, expected) in actual.iter().zip(expected()) {
+                for (actual, expected) in
+                    actual.chromosome().iter().zip(expected.chromosome().iter())
+                {
+                    assert_ne!(actual, expected);
+                    approx::assert_relative_eq!(actual, expected, epsilon = 0.05);
                 }
             }
         }
+    }
+
+    #[test]
+    fn selection_intensity() {
+        let population = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let selected = [7.0, 9.0, 5.0, 7.0];
+
+        // mean = 5, stddev = 2, mean of the selected ones = 7
+        approx::assert_relative_eq!(super::selection_intensity(&population, &selected), 1.0);
+        approx::assert_relative_eq!(super::selection_intensity(&[3.0, 3.0], &[3.0]), 0.0);
+    }
+
+    #[test]
+    fn tournament_pressure_sweep() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let results = super::tournament_pressure_sweep(
+            &mut rng,
+            &[2, 4, 8],
+            |selection| {
+                GeneticAlgorithm::new(
+                    selection,
+                    UniformCrossover::new(),
+                    GaussianMutation::new(0.5, 0.5),
+                )
+            },
+            || (0..30).map(|_| individual(&[0.0, 0.0, 0.0])).collect(),
+            500,
+            20.0,
+        );
+
+        let generations: Vec<_> = results
+            .iter()
+            .map(|result| {
+                assert!(result.best_fitness >= 20.0);
+                result.convergence_generation.expect("didn't converge")
+            })
+            .collect();
+
+        assert!(
+            generations.windows(2).all(|pair| pair[1] < pair[0]),
+            "generations = {:?}",
+            generations
+        );
+    }
+
+    #[test]
+    fn novelty_selection() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        // Behavior is the chromosome itself; the last individual is far
+        // away from all the others
+        let method = NoveltySelection::new(2, |chromosome| chromosome.iter().copied().collect());
+
+        let population = vec![
+            individual(&[0.0, 0.0]),
+            individual(&[0.1, 0.0]),
+            individual(&[0.0, 0.1]),
+            individual(&[0.1, 0.1]),
+            individual(&[5.0, 5.0]),
+        ];
+
+        let mut histogram = [0; 5];
+
+        for _ in 0..1000 {
+            let selected = method.select(&mut rng, &population);
+            let idx = population
+                .iter()
+                .position(|individual| std::ptr::eq(individual, selected))
+                .unwrap();
+
+            histogram[idx] += 1;
+        }
 
-        #[test]
-        fn roulette_selection() {
-            let method = RouletteWheelSelection::new();
-            let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let most_selected = (0..5).max_by_key(|&idx| histogram[idx]).unwrap();
 
-            let population = vec![
-                TestIndividual::new(2.0),
-                TestIndividual::new(1.0),
-                TestIndividual::new(4.0),
-                TestIndividual::new(3.0),
-            ];
+        assert_eq!(most_selected, 4, "{:?}", histogram);
+    }
 
-            let actual_histogram: BTreeMap<i32, _> = (0..1000)
-                .map(|_| method.select(&mut rng, &population))
-                .fold(Default::default(), |mut histogram, individual| {
-                    *histogram.entry(individual.fitness() as _).or_default() += 1;
+    #[test]
+    fn expected_offspring() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-                    histogram
-                });
+        let population = vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ];
 
-            let expected_histogram = maplit::btreemap! {
-                1 => 98,
-                2 => 202,
-                3 => 278,
-                4 => 422,
-            };
+        let expected =
+            super::expected_offspring(&mut rng, &RouletteWheelSelection::new(), &population, 10);
 
-            assert_eq!(actual_histogram, expected_histogram);
-        }
+        let best = (0..expected.len())
+            .max_by(|&a, &b| expected[a].total_cmp(&expected[b]))
+            .unwrap();
+
+        assert_eq!(best, 2);
+
+        // Roulette selects proportionally to fitness: 10 * 4 / 10
+        approx::assert_relative_eq!(expected[2], 4.0, epsilon = 0.3);
+        approx::assert_relative_eq!(expected.iter().sum::<f32>(), 10.0, epsilon = 1e-4);
     }
 
-    mod chromosomes {
+    mod offspring_count {
         use super::*;
 
-        impl PartialEq for Chromosome {
-            fn eq(&self, other: &Self) -> bool {
-                approx::relative_eq!(self.genes.as_slice(), other.genes.as_slice(),)
-            }
-        }
+        fn evolve(truncate: bool) -> Vec<TestIndividual> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        fn chromosome() -> Chromosome {
-            Chromosome {
-                genes: vec![3.0, 1.0, 2.0],
-            }
+            // Each child is an unmodified clone of its single parent, so
+            // that its fitness is the same as its parent's
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.0, 0.0),
+            )
+            .with_crossover_chance(0.0)
+            .with_offspring_count(20)
+            .with_truncate_offspring(truncate);
+
+            let population: Vec<_> = (0..10)
+                .map(|idx| individual(&[idx as f32, 1.0, 2.0]))
+                .collect();
+
+            ga.evolve(&mut rng, &population)
         }
 
         #[test]
-        fn len() {
-            assert_eq!(chromosome().len(), 3);
+        fn without_truncation() {
+            assert_eq!(evolve(false).len(), 20);
         }
 
         #[test]
-        fn iter() {
-            let chromosome = chromosome();
-            let genes: Vec<_> = chromosome.iter().collect();
+        fn with_truncation() {
+            let mut expected = evolve(false);
+            expected.sort_by(compare_fitness_descending);
+            expected.truncate(10);
 
-            assert_eq!(genes.len(), 3);
-            assert_eq!(genes[0], &3.0);
-            assert_eq!(genes[1], &1.0);
-            assert_eq!(genes[2], &2.0);
+            assert_eq!(evolve(true), expected);
         }
+    }
 
-        #[test]
-        fn iter_mut() {
-            let mut chromosome = chromosome();
+    mod immigrants {
+        use super::*;
 
-            chromosome.iter_mut().for_each(|gene| {
-                *gene *= 10.0;
+        fn evolve(seed: u64) -> Vec<TestIndividual> {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+            // Immigrants are easy to tell apart, since all of their genes
+            // are strongly negative
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.0, 0.0),
+            )
+            .with_immigrants(0.3, |rng| {
+                (0..3).map(|_| rng.gen_range(-200.0..-100.0)).collect()
             });
 
-            let genes: Vec<_> = chromosome.iter().collect();
+            let population: Vec<_> = (0..10)
+                .map(|idx| individual(&[idx as f32, 1.0, 2.0]))
+                .collect();
 
-            assert_eq!(genes.len(), 3);
-            assert_eq!(genes[0], &30.0);
-            assert_eq!(genes[1], &10.0);
-            assert_eq!(genes[2], &20.0);
+            ga.evolve(&mut rng, &population)
+        }
+
+        fn is_immigrant(individual: &TestIndividual) -> bool {
+            individual.chromosome().iter().all(|&gene| gene <= -100.0)
         }
 
         #[test]
-        fn index() {
-            let chromosome = chromosome();
+        fn fraction() {
+            let population = evolve(1);
 
-            assert_eq!(chromosome[0], 3.0);
-            assert_eq!(chromosome[1], 1.0);
-            assert_eq!(chromosome[2], 2.0);
+            assert_eq!(population.len(), 10);
+            assert_eq!(population.iter().filter(|i| is_immigrant(i)).count(), 3);
         }
 
         #[test]
-        fn from_iter() {
-            let chromosome: Chromosome = vec![3.0, 1.0, 2.0].into_iter().collect();
+        fn replaces_worst() {
+            // Each child is an unmodified clone of its single parent, so
+            // that it's as fit as its parent
+            let evolve = |immigrants: bool| {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-            assert_eq!(chromosome[0], 3.0);
-            assert_eq!(chromosome[1], 1.0);
-            assert_eq!(chromosome[2], 2.0);
-        }
-    }
+                let ga = GeneticAlgorithm::new(
+                    RouletteWheelSelection::new(),
+                    UniformCrossover::new(),
+                    GaussianMutation::new(0.0, 0.0),
+                )
+                .with_crossover_chance(0.0);
 
-    mod crossover {
-        use super::*;
-        use rand_chacha::ChaCha8Rng;
+                let ga = if immigrants {
+                    ga.with_immigrants(0.3, |rng| {
+                        (0..3).map(|_| rng.gen_range(-200.0..-100.0)).collect()
+                    })
+                } else {
+                    ga
+                };
+
+                let population: Vec<_> = (0..10)
+                    .map(|idx| individual(&[idx as f32, 1.0, 2.0]))
+                    .collect();
+
+                ga.evolve(&mut rng, &population)
+            };
+
+            let mut expected = evolve(false);
+            expected.sort_by(compare_fitness_descending);
+            expected.truncate(7);
+
+            let mut actual: Vec<_> = evolve(true)
+                .into_iter()
+                .filter(|individual| !is_immigrant(individual))
+                .collect();
+
+            actual.sort_by(compare_fitness_descending);
+
+            assert_eq!(actual, expected);
+        }
 
         #[test]
-        fn uniform() {
+        fn fraction_of_offspring() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-            let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
-            let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
+            // Half of the population are elites, so out of the remaining
+            // five offspring, 40% makes two immigrants
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.0, 0.0),
+            )
+            .with_elitism(5)
+            .with_immigrants(0.4, |rng| {
+                (0..3).map(|_| rng.gen_range(-200.0..-100.0)).collect()
+            });
 
-            let child = UniformCrossover::new().crossover(&mut rng, &parent_a, &parent_b);
+            let population: Vec<_> = (0..10)
+                .map(|idx| individual(&[idx as f32, 1.0, 2.0]))
+                .collect();
 
-            let diff_a = child.iter().zip(parent_a).filter(|(c, p)| *c != p).count();
-            let diff_b = child.iter().zip(parent_b).filter(|(c, p)| *c != p).count();
+            let population = ga.evolve(&mut rng, &population);
 
-            assert_eq!(diff_a, 49);
-            assert_eq!(diff_b, 51);
+            assert_eq!(population.len(), 10);
+            assert_eq!(population.iter().filter(|i| is_immigrant(i)).count(), 2);
+        }
+
+        #[test]
+        fn reproducible() {
+            assert_eq!(evolve(1), evolve(1));
+            assert_ne!(evolve(1), evolve(2));
         }
     }
 
-    mod mutation {
+    mod repair {
         use super::*;
-        use rand::SeedableRng;
-        use rand_chacha::ChaCha8Rng;
 
-        fn actual(chance: f32, coeff: f32) -> Vec<f32> {
-            let mut child = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
+        fn assert_on_simplex(chromosome: &Chromosome) {
+            assert!(chromosome.iter().all(|&gene| gene >= 0.0));
+            approx::assert_relative_eq!(chromosome.iter().sum::<f32>(), 1.0, epsilon = 1e-5);
+        }
 
-            let mut rng = ChaCha8Rng::from_seed(Default::default());
+        #[test]
+        fn simplex() {
+            let mut chromosome: Chromosome = vec![2.0, -1.0, 6.0].into_iter().collect();
+            SimplexRepair::new().repair(&mut chromosome);
 
-            GaussianMutation::new(chance, coeff).mutate(&mut rng, &mut child);
+            assert_eq!(chromosome, vec![0.25, 0.0, 0.75].into_iter().collect());
 
-            child.into_iter().collect()
-        }
+            let mut chromosome: Chromosome = vec![-2.0, -1.0].into_iter().collect();
+            SimplexRepair::new().repair(&mut chromosome);
 
-        mod given_zero_change {
-            fn actual(coeff: f32) -> Vec<f32> {
-                super::actual(0.0, coeff)
-            }
+            assert_eq!(chromosome, vec![0.5, 0.5].into_iter().collect());
+        }
 
-            mod and_zero_coefficient {
-                use super::*;
+        #[test]
+        fn evolve() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-                #[test]
-                fn does_not_change_the_original_chromosome() {
-                    let actual = actual(0.0);
-                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(1.0, 1.0),
+            )
+            .with_repair(SimplexRepair::new());
 
-                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
-                }
-            }
-            mod and_nonzero_coefficient {
-                use super::*;
+            let mut population = vec![
+                individual(&[0.2, 0.3, 0.5]),
+                individual(&[0.1, 0.1, 0.8]),
+                individual(&[0.6, 0.2, 0.2]),
+                individual(&[0.3, 0.3, 0.4]),
+            ];
 
-                #[test]
-                fn does_not_change_the_original_chromosome() {
-                    let actual = actual(0.5);
-                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+            for _ in 0..10 {
+                population = ga.evolve(&mut rng, &population);
 
-                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
+                for individual in &population {
+                    assert_on_simplex(individual.chromosome());
                 }
             }
         }
+    }
 
-        mod given_fifty_fifty_change {
-            fn actual(coeff: f32) -> Vec<f32> {
-                super::actual(0.5, coeff)
-            }
+    #[test]
+    fn statistics_percentile() {
+        let population = vec![
+            individual(&[4.0]),
+            individual(&[1.0]),
+            individual(&[5.0]),
+            individual(&[3.0]),
+            individual(&[2.0]),
+        ];
 
-            mod and_zero_coeddicient {
-                use super::*;
-                #[test]
-                fn does_not_change_the_original_chromosome() {
-                    let actual = actual(0.0);
-                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let statistics = Statistics::new(&population);
 
-                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
-                }
-            }
+        approx::assert_relative_eq!(statistics.percentile(0.0), 1.0);
+        approx::assert_relative_eq!(statistics.percentile(50.0), 3.0);
+        approx::assert_relative_eq!(statistics.percentile(100.0), 5.0);
 
-            mod and_nonzero_coefficient {
-                use super::*;
+        // Rank 0.9 * 4 = 3.6, i.e. 60% of the way between 4.0 and 5.0
+        approx::assert_relative_eq!(statistics.percentile(90.0), 4.6);
+    }
 
-                #[test]
-                fn slightly_changes_the_original_chromosome() {
-                    let actual = actual(0.5);
-                    let expected = vec![1.0, 1.7756249, 3.0, 4.1596804, 5.0];
+    #[test]
+    fn fitness_pipeline() {
+        let pipeline = FitnessPipeline::new()
+            .then(FitnessTransform::Shift(1.0))
+            .then(FitnessTransform::Power(2.0));
 
-                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
-                }
-            }
-        }
+        approx::assert_relative_eq!(
+            pipeline.apply(&[0.0, 1.0, 2.0, -1.0]).as_slice(),
+            [1.0, 4.0, 9.0, 0.0].as_slice()
+        );
 
-        mod given_max_chance {
-            fn actual(coeff: f32) -> Vec<f32> {
-                super::actual(1.0, coeff)
-            }
+        let pipeline = FitnessPipeline::new()
+            .then(FitnessTransform::Scale(10.0))
+            .then(FitnessTransform::Clamp(0.0, 15.0))
+            .then(FitnessTransform::Rank);
 
-            mod and_zero_coeddicient {
-                use super::*;
+        approx::assert_relative_eq!(
+            pipeline.apply(&[3.0, -1.0, 1.0, 2.0, 0.5]).as_slice(),
+            [4.0, 1.0, 3.0, 4.0, 2.0].as_slice()
+        );
+    }
 
-                #[test]
-                fn does_not_change_the_original_chromosome() {
-                    let actual = actual(0.0);
-                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+    #[test]
+    fn statistics_csv() {
+        let statistics = vec![
+            Statistics::new(&[individual(&[1.0]), individual(&[2.0]), individual(&[6.0])]),
+            Statistics::new(&[individual(&[2.5]), individual(&[3.5])]),
+        ];
 
-                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
-                }
-            }
+        assert_eq!(statistics[0].to_csv_row(7), "7,1,6,3,2");
 
-            mod and_nonzero_coefficient {
-                use super::*;
+        assert_eq!(
+            Statistics::to_csv(&statistics),
+            "generation,min,max,avg,median\n0,1,6,3,2\n1,2.5,3.5,3,3\n"
+        );
+    }
 
-                #[test]
-                fn entirely_changes_the_original_chromosome() {
-                    let actual = actual(0.5);
-                    let expected = vec![1.4545316, 2.1162078, 2.7756248, 3.9505124, 4.638691];
+    mod crossover_chance {
+        use super::*;
 
-                    approx::assert_relative_eq!(actual.as_slice(), expected.as_slice(),);
-                }
+        fn evolve(crossover_chance: f32) -> (Vec<TestIndividual>, Vec<TestIndividual>) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = GeneticAlgorithm::new(
+                RouletteWheelSelection::new(),
+                UniformCrossover::new(),
+                GaussianMutation::new(0.0, 0.0),
+            )
+            .with_crossover_chance(crossover_chance);
+
+            let population = vec![
+                individual(&[1.0, 2.0, 3.0, 4.0]),
+                individual(&[5.0, 6.0, 7.0, 8.0]),
+                individual(&[9.0, 10.0, 11.0, 12.0]),
+                individual(&[13.0, 14.0, 15.0, 16.0]),
+            ];
+
+            let offspring = (0..25)
+                .flat_map(|_| ga.evolve(&mut rng, &population))
+                .collect();
+
+            (population, offspring)
+        }
+
+        #[test]
+        fn never() {
+            let (population, offspring) = evolve(0.0);
+
+            for child in &offspring {
+                assert!(
+                    population.contains(child),
+                    "{:?} has more than one parent",
+                    child
+                );
             }
         }
+
+        #[test]
+        fn always() {
+            let (population, offspring) = evolve(1.0);
+
+            assert!(offspring.iter().any(|child| !population.contains(child)));
+        }
     }
 
-    fn individual(genes: &[f32]) -> TestIndividual {
-        let chromosome = genes.iter().cloned().collect();
+    #[test]
+    fn population_gene_variance() {
+        let population = vec![
+            individual(&[1.0, 2.0, 3.0]),
+            individual(&[1.0, 4.0, 0.0]),
+            individual(&[1.0, 6.0, 0.0]),
+        ];
 
-        TestIndividual::create(chromosome)
+        approx::assert_relative_eq!(
+            super::population_gene_variance(&population).as_slice(),
+            [0.0, 8.0 / 3.0, 2.0].as_slice()
+        );
     }
 
     #[test]
-    fn test() {
+    fn adaptive_mutation() {
         let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        let ga = GeneticAlgorithm::new(
-            RouletteWheelSelection::new(),
-            UniformCrossover::new(),
-            GaussianMutation::new(0.5, 0.5),
+        let population = vec![
+            individual(&[1.0, -10.0]),
+            individual(&[1.0, 0.0]),
+            individual(&[1.0, 10.0]),
+        ];
+
+        let mutation = AdaptiveMutation::from_population(1.0, 1.0, &population);
+        let mut change = [0.0; 2];
+
+        for _ in 0..100 {
+            let mut child: Chromosome = vec![0.0, 0.0].into_iter().collect();
+            mutation.mutate(&mut rng, &mut child);
+
+            for (change, gene) in change.iter_mut().zip(child.iter()) {
+                *change += gene.abs();
+            }
+        }
+
+        // The first gene has collapsed, so it should've been changed way
+        // more than the diverse second one
+        assert!(change[0] > 10.0 * change[1], "{:?}", change);
+    }
+
+    #[test]
+    fn population_principal_component() {
+        // Spread along the second gene only
+        let population = vec![
+            individual(&[1.0, -3.0, 2.0]),
+            individual(&[1.0, 0.0, 2.0]),
+            individual(&[1.0, 1.0, 2.0]),
+            individual(&[1.0, 5.0, 2.0]),
+        ];
+
+        approx::assert_relative_eq!(
+            super::population_principal_component(&population).as_slice(),
+            [0.0, 1.0, 0.0].as_slice(),
+            epsilon = 1e-5
         );
 
-        let mut population = vec![
-            individual(&[0.0, 0.0, 0.0]),
-            individual(&[1.0, 1.0, 1.0]),
-            individual(&[1.0, 2.0, 1.0]),
-            individual(&[1.0, 2.0, 4.0]),
+        // Spread along the diagonal, plus a bit of noise
+        let population = vec![
+            individual(&[-2.0, 2.0]),
+            individual(&[-1.0, 1.1]),
+            individual(&[1.0, -0.9]),
+            individual(&[2.0, -2.0]),
         ];
 
-        for _ in 0..10 {
-            population = ga.evolve(&mut rng, &population);
-        }
+        let component = super::population_principal_component(&population);
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
 
-        let expected_population = vec![
-            individual(&[0.4476949, 2.0648358, 4.3058133]),
-            individual(&[1.2126867, 1.5538777, 2.886911]),
-            individual(&[1.0617678, 2.265739, 4.428764]),
-            individual(&[0.95909685, 2.4618788, 4.024733]),
+        approx::assert_relative_eq!(component[0].abs(), diagonal, epsilon = 0.02);
+        approx::assert_relative_eq!(component[1].abs(), diagonal, epsilon = 0.02);
+        assert!(component[0] * component[1] < 0.0);
+    }
+
+    #[test]
+    fn population_centroid() {
+        let population = vec![
+            individual(&[1.0, 2.0, 3.0]),
+            individual(&[3.0, 2.0, 0.0]),
+            individual(&[2.0, 5.0, 0.0]),
         ];
 
-        assert_eq!(population, expected_population);
+        assert_eq!(
+            super::population_centroid(&population),
+            vec![2.0, 3.0, 1.0].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn best_individual() {
+        let population = vec![
+            individual(&[1.0, 1.0]),
+            individual(&[4.0, 0.0]),
+            individual(&[3.0, 0.0]),
+            individual(&[2.0, 2.0]),
+        ];
+
+        let best = super::best_individual(&population);
+
+        assert!(std::ptr::eq(best, &population[1]));
     }
 }
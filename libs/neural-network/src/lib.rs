@@ -1,58 +1,792 @@
 use rand::prelude::*;
+use std::fmt::{self, Debug};
+use std::iter::{once, Sum};
+use std::ops::RangeInclusive;
+use std::ops::{Add, Mul, Sub};
 
 #[derive(Debug)]
-pub struct Network {
-    layers: Vec<Layer>,
+pub struct Network<F = f32> {
+    layers: Vec<Layer<F>>,
 }
 
 #[derive(Clone, Debug)]
-struct Layer {
-    neurons: Vec<Neuron>,
+struct Layer<F> {
+    neurons: Vec<Neuron<F>>,
 }
 
 pub struct LayerTopology {
     pub neurons: usize,
 }
+impl LayerTopology {
+    /// Returns how many values (biases and weights) of `Network::weights()`
+    /// each layer of a network with given topology takes
+    pub fn weight_counts(layers: &[LayerTopology]) -> Vec<usize> {
+        layers
+            .windows(2)
+            .map(|layers| layers[1].neurons * (layers[0].neurons + 1))
+            .collect()
+    }
+
+    /// Like `weight_counts()`, but for `Network::genes()` - i.e. including
+    /// each neuron's activation selector gene
+    pub fn gene_counts(layers: &[LayerTopology]) -> Vec<usize> {
+        layers
+            .windows(2)
+            .map(|layers| layers[1].neurons * (layers[0].neurons + 2))
+            .collect()
+    }
+}
 
 #[derive(Clone, Debug)]
-struct Neuron {
-    bias: f32,
-    weights: Vec<f32>,
+struct Neuron<F> {
+    bias: F,
+    weights: Vec<F>,
+    activation: Activation,
+}
+
+/// What a value returned from `Network::weights()` (or `Network::genes()`)
+/// is used for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightRole {
+    Bias,
+    Weight,
+
+    /// Activation selector gene; present only in `Network::genes()`
+    Activation,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Activation {
+    #[default]
+    Relu,
+
+    /// Like `Relu`, but negative inputs are scaled by `slope` instead of
+    /// being zeroed, so that neurons can't get permanently stuck at zero
+    LeakyRelu { slope: f32 },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetworkError {
+    /// Output layer of one network doesn't match input layer of the other
+    SizeMismatch { outputs: usize, inputs: usize },
+
+    /// Network has no layers, or one of its layers has no neurons
+    Empty,
+
+    /// Neuron's weight count doesn't match the width of the preceding layer
+    InconsistentWeights {
+        layer: usize,
+        neuron: usize,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeMismatch { outputs, inputs } => write!(
+                f,
+                "cannot feed {} outputs into a network expecting {} inputs",
+                outputs, inputs
+            ),
+
+            Self::Empty => write!(f, "network has an empty layer"),
+
+            Self::InconsistentWeights {
+                layer,
+                neuron,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "neuron #{} in layer #{} has {} weights, but the preceding layer has {} neurons",
+                neuron, layer, actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+/// Floating-point type a network computes in - `f32` by default, `f64` for
+/// when rounding errors matter
+pub trait Float:
+    Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Sum
+{
+    fn from_f32(value: f32) -> Self;
+    fn to_f32(self) -> f32;
+    fn abs(self) -> Self;
+}
+
+impl Float for f32 {
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+}
+
+impl Float for f64 {
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
 }
 
-impl Network {
+impl<F> Network<F>
+where
+    F: Float,
+{
     pub fn random(rng: &mut dyn rand::RngCore, layers: &[LayerTopology]) -> Self {
+        Self::random_with_range(rng, layers, -1.0..=1.0, -1.0..=1.0)
+    }
+
+    /// Like `random()`, but with biases and weights drawn from given ranges
+    /// instead of `-1.0..=1.0`
+    pub fn random_with_range(
+        rng: &mut dyn rand::RngCore,
+        layers: &[LayerTopology],
+        bias_range: RangeInclusive<f32>,
+        weight_range: RangeInclusive<f32>,
+    ) -> Self {
+        assert!(layers.len() > 1);
+
+        let layers = layers
+            .windows(2)
+            .map(|layers| {
+                Layer::random_with_range(
+                    rng,
+                    layers[0].neurons,
+                    layers[1].neurons,
+                    bias_range.clone(),
+                    weight_range.clone(),
+                )
+            })
+            .collect();
+
+        Self { layers }
+    }
+
+    pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = F>) -> Self {
+        assert!(layers.len() > 1);
+
+        let mut weights = weights.into_iter();
+
+        let layers = layers
+            .windows(2)
+            .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
+            .collect();
+
+        if weights.next().is_some() {
+            panic!("got too many weights");
+        }
+
+        Self { layers }
+    }
+
+    pub fn weights(&self) -> impl Iterator<Item = F> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
+            .copied()
+    }
+
+    /// Like `weights()`, but each neuron's values are preceded by a gene
+    /// selecting its activation function (see `Activation::to_gene()`), so
+    /// that activations can be evolved alongside weights
+    pub fn genes(&self) -> impl Iterator<Item = F> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .flat_map(|neuron| {
+                once(F::from_f32(neuron.activation.to_gene()))
+                    .chain(once(neuron.bias))
+                    .chain(neuron.weights.iter().copied())
+            })
+    }
+
+    /// Inverse of `genes()`; since genes don't encode `LeakyRelu`'s slope,
+    /// it has to be provided separately
+    pub fn from_genes(
+        layers: &[LayerTopology],
+        genes: impl IntoIterator<Item = F>,
+        leaky_relu_slope: f32,
+    ) -> Self {
         assert!(layers.len() > 1);
 
+        let mut genes = genes.into_iter();
+
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| {
+                let neurons = (0..layers[1].neurons)
+                    .map(|_| {
+                        let activation = genes.next().expect("got not enough genes");
+
+                        Neuron {
+                            activation: Activation::from_gene(
+                                activation.to_f32(),
+                                leaky_relu_slope,
+                            ),
+                            ..Neuron::from_weights(layers[0].neurons, &mut genes)
+                        }
+                    })
+                    .collect();
+
+                Layer { neurons }
+            })
             .collect();
 
+        if genes.next().is_some() {
+            panic!("got too many genes");
+        }
+
         Self { layers }
     }
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+    /// Returns activation function of each neuron, layer by layer
+    pub fn activations(&self) -> impl Iterator<Item = Activation> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .map(|neuron| neuron.activation)
+    }
+
+    /// Changes activation function of all the neurons
+    pub fn with_activation(mut self, activation: Activation) -> Self {
+        for neuron in self.layers.iter_mut().flat_map(|layer| &mut layer.neurons) {
+            neuron.activation = activation;
+        }
+
+        self
+    }
+
+    /// Returns roles of values yielded by `weights()`, in the same order
+    pub fn weight_roles(&self) -> impl Iterator<Item = WeightRole> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .flat_map(|neuron| {
+                once(WeightRole::Bias).chain(neuron.weights.iter().map(|_| WeightRole::Weight))
+            })
+    }
+
+    /// Returns roles of values yielded by `genes()`, in the same order
+    pub fn gene_roles(&self) -> impl Iterator<Item = WeightRole> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .flat_map(|neuron| {
+                once(WeightRole::Activation)
+                    .chain(once(WeightRole::Bias))
+                    .chain(neuron.weights.iter().map(|_| WeightRole::Weight))
+            })
+    }
+
+    pub fn propagate(&self, inputs: Vec<F>) -> Vec<F> {
         self.layers
             .iter()
             .fold(inputs, |inputs, layer| layer.propagate(inputs))
     }
+
+    /// Side-effect-free forward pass - it never touches any RNG and, no
+    /// matter the depth, allocates just two scratch buffers.
+    #[must_use]
+    pub fn infer(&self, inputs: &[F]) -> Vec<F> {
+        let mut current = inputs.to_vec();
+        let mut next = Vec::new();
+
+        for layer in &self.layers {
+            next.clear();
+            next.extend(
+                layer
+                    .neurons
+                    .iter()
+                    .map(|neuron| neuron.propagate(&current)),
+            );
+
+            std::mem::swap(&mut current, &mut next);
+        }
+
+        current
+    }
+
+    /// Returns outputs of each layer, the last one being the same as what
+    /// `propagate()` returns
+    pub fn propagate_trace(&self, inputs: &[F]) -> Vec<Vec<F>> {
+        let mut trace: Vec<Vec<F>> = Vec::with_capacity(self.layers.len());
+
+        for layer in &self.layers {
+            let inputs = trace.last().cloned().unwrap_or_else(|| inputs.to_vec());
+
+            trace.push(layer.propagate(inputs));
+        }
+
+        trace
+    }
+
+    /// Like `propagate()`, but with outputs of neurons whose `mask` entry
+    /// (one `Vec` per layer, one `bool` per neuron) is `false` zeroed, so
+    /// that ablations are reproducible without involving any RNG
+    pub fn propagate_with_mask(&self, inputs: Vec<F>, mask: &[Vec<bool>]) -> Vec<F> {
+        assert_eq!(
+            mask.len(),
+            self.layers.len(),
+            "got mask for wrong number of layers"
+        );
+
+        self.layers
+            .iter()
+            .zip(mask)
+            .fold(inputs, |inputs, (layer, mask)| {
+                assert_eq!(
+                    mask.len(),
+                    layer.neurons.len(),
+                    "got mask for wrong number of neurons"
+                );
+
+                layer
+                    .propagate(inputs)
+                    .into_iter()
+                    .zip(mask)
+                    .map(|(output, &keep)| if keep { output } else { F::default() })
+                    .collect()
+            })
+    }
+
+    /// Returns how many neurons output zero for each of given samples
+    pub fn dead_neuron_count(&self, sample_inputs: &[Vec<F>]) -> usize {
+        let neurons = self.layers.iter().map(|layer| layer.neurons.len()).sum();
+        let mut alive = vec![false; neurons];
+
+        for inputs in sample_inputs {
+            let outputs = self.propagate_trace(inputs).into_iter().flatten();
+
+            for (output, alive) in outputs.zip(&mut alive) {
+                *alive |= output != F::default();
+            }
+        }
+
+        alive.into_iter().filter(|alive| !alive).count()
+    }
+
+    /// Looks for an input maximizing given output by gradient ascent,
+    /// starting from all zeros; the gradient is estimated numerically.
+    ///
+    /// Inputs are not bounded in any way, so what matters is how they
+    /// compare to each other rather than their absolute values.
+    pub fn maximize_output(&self, output_index: usize, steps: usize, lr: F) -> Vec<F> {
+        const EPSILON: f32 = 1e-3;
+
+        let mut inputs = vec![F::default(); self.input_size()];
+
+        for _ in 0..steps {
+            let gradient: Vec<_> = (0..inputs.len())
+                .map(|idx| {
+                    let mut probe = inputs.clone();
+
+                    probe[idx] = inputs[idx] + F::from_f32(EPSILON);
+                    let above = self.infer(&probe)[output_index];
+
+                    probe[idx] = inputs[idx] - F::from_f32(EPSILON);
+                    let below = self.infer(&probe)[output_index];
+
+                    (above - below) * F::from_f32(0.5 / EPSILON)
+                })
+                .collect();
+
+            for (input, gradient) in inputs.iter_mut().zip(gradient) {
+                *input = *input + lr * gradient;
+            }
+        }
+
+        inputs
+    }
+
+    /// Appends `other`'s layers after this network's ones, so that this
+    /// network's outputs become `other`'s inputs
+    pub fn stack(mut self, other: Network<F>) -> Result<Self, NetworkError> {
+        if self.output_size() != other.input_size() {
+            return Err(NetworkError::SizeMismatch {
+                outputs: self.output_size(),
+                inputs: other.input_size(),
+            });
+        }
+
+        self.layers.extend(other.layers);
+
+        Ok(self)
+    }
+
+    /// Checks that the network's shape is consistent - i.e. that each
+    /// neuron has as many weights as there are neurons in the preceding
+    /// layer (or inputs, for the first layer)
+    pub fn validate(&self) -> Result<(), NetworkError> {
+        if self.layers.is_empty() || self.layers.iter().any(|layer| layer.neurons.is_empty()) {
+            return Err(NetworkError::Empty);
+        }
+
+        let mut expected = self.input_size();
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            for (neuron_idx, neuron) in layer.neurons.iter().enumerate() {
+                if neuron.weights.len() != expected {
+                    return Err(NetworkError::InconsistentWeights {
+                        layer: layer_idx,
+                        neuron: neuron_idx,
+                        expected,
+                        actual: neuron.weights.len(),
+                    });
+                }
+            }
+
+            expected = layer.neurons.len();
+        }
+
+        Ok(())
+    }
+
+    fn input_size(&self) -> usize {
+        self.layers[0].neurons[0].weights.len()
+    }
+
+    fn output_size(&self) -> usize {
+        self.layers[self.layers.len() - 1].neurons.len()
+    }
+
+    /// Returns how many parameters (biases and weights) actually matter -
+    /// i.e. nudging them by `epsilon` changes any of the network's outputs by
+    /// more than `epsilon²` for at least one of given samples.
+    ///
+    /// Smaller changes are treated as rounding noise or as coming from
+    /// parameters that barely matter.
+    pub fn effective_params(&self, samples: &[Vec<F>], epsilon: F) -> usize {
+        let threshold = epsilon * epsilon;
+        let expected: Vec<_> = samples.iter().map(|inputs| self.infer(inputs)).collect();
+        let params = self.weights().count();
+
+        (0..params)
+            .filter(|&idx| {
+                let mut network = Self {
+                    layers: self.layers.clone(),
+                };
+
+                let param = network
+                    .layers
+                    .iter_mut()
+                    .flat_map(|layer| layer.neurons.iter_mut())
+                    .flat_map(|neuron| once(&mut neuron.bias).chain(neuron.weights.iter_mut()))
+                    .nth(idx)
+                    .unwrap();
+
+                *param = *param + epsilon;
+
+                samples.iter().zip(&expected).any(|(inputs, expected)| {
+                    network
+                        .infer(inputs)
+                        .into_iter()
+                        .zip(expected)
+                        .any(|(actual, &expected)| (actual - expected).abs() > threshold)
+                })
+            })
+            .count()
+    }
+
+    /// Bins all the weights (excluding biases) into `bins` equally wide
+    /// buckets spanning `range`; weights outside of the range land in the
+    /// first or the last bucket.
+    pub fn weight_histogram(&self, bins: usize, range: (f32, f32)) -> Vec<usize> {
+        assert!(bins > 0);
+        assert!(range.0 < range.1);
+
+        let mut histogram = vec![0; bins];
+
+        let weights = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .flat_map(|neuron| neuron.weights.iter());
+
+        for weight in weights {
+            let position = (weight.to_f32() - range.0) / (range.1 - range.0);
+            let bin = (position * bins as f32).max(0.0) as usize;
+
+            histogram[bin.min(bins - 1)] += 1;
+        }
+
+        histogram
+    }
+
+    /// Returns, for each input, how much zeroing it changes the outputs -
+    /// i.e. the absolute change summed over outputs, averaged over given
+    /// samples.
+    pub fn input_influence(&self, samples: &[Vec<F>]) -> Vec<f32> {
+        let mut influence = vec![0.0; self.input_size()];
+
+        if samples.is_empty() {
+            return influence;
+        }
+
+        for inputs in samples {
+            let expected = self.infer(inputs);
+
+            for (idx, influence) in influence.iter_mut().enumerate() {
+                let mut inputs = inputs.clone();
+                inputs[idx] = F::default();
+
+                *influence += self
+                    .infer(&inputs)
+                    .into_iter()
+                    .zip(&expected)
+                    .map(|(actual, &expected)| (actual - expected).abs().to_f32())
+                    .sum::<f32>();
+            }
+        }
+
+        for influence in &mut influence {
+            *influence /= samples.len() as f32;
+        }
+
+        influence
+    }
+
+    /// Returns how differently the networks behave - i.e. Euclidean distance
+    /// between their outputs, averaged over given probe inputs.
+    ///
+    /// Unlike comparing weights, this treats networks computing the same
+    /// function (e.g. with hidden neurons shuffled) as identical.
+    pub fn behavioral_distance(&self, other: &Network<F>, probes: &[Vec<F>]) -> f32 {
+        if probes.is_empty() {
+            return 0.0;
+        }
+
+        let total: f32 = probes
+            .iter()
+            .map(|inputs| {
+                let a = self.infer(inputs);
+                let b = other.infer(inputs);
+
+                assert_eq!(
+                    a.len(),
+                    b.len(),
+                    "networks have different number of outputs"
+                );
+
+                a.into_iter()
+                    .zip(b)
+                    .map(|(a, b)| (a - b).to_f32().powi(2))
+                    .sum::<f32>()
+                    .sqrt()
+            })
+            .sum();
+
+        total / probes.len() as f32
+    }
+
+    /// Describes the network in Graphviz's DOT language - inputs are named
+    /// `i{input}`, neurons `n{layer}_{neuron}` (labeled with their biases)
+    /// and edges are labeled with weights.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n    rankdir=LR;\n");
+
+        for input in 0..self.input_size() {
+            dot += &format!("    i{};\n", input);
+        }
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            for (neuron_idx, neuron) in layer.neurons.iter().enumerate() {
+                let node = format!("n{}_{}", layer_idx, neuron_idx);
+
+                dot += &format!("    {} [label=\"{}\"];\n", node, neuron.bias.to_f32());
+
+                for (input_idx, weight) in neuron.weights.iter().enumerate() {
+                    let input = if layer_idx == 0 {
+                        format!("i{}", input_idx)
+                    } else {
+                        format!("n{}_{}", layer_idx - 1, input_idx)
+                    };
+
+                    dot += &format!(
+                        "    {} -> {} [label=\"{}\"];\n",
+                        input,
+                        node,
+                        weight.to_f32()
+                    );
+                }
+            }
+        }
+
+        dot += "}\n";
+        dot
+    }
+
+    /// Returns the number of multiply-adds a single forward pass takes
+    /// (biases and activations are not counted)
+    pub fn flops(&self) -> usize {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .map(|neuron| neuron.weights.len())
+            .sum()
+    }
+
+    /// Zeroes every weight whose magnitude is below `threshold`, returning
+    /// how many weights got pruned (including the ones that were zero
+    /// already); biases are left untouched.
+    pub fn prune(&mut self, threshold: F) -> usize {
+        self.layers
+            .iter_mut()
+            .flat_map(|layer| layer.neurons.iter_mut())
+            .flat_map(|neuron| neuron.weights.iter_mut())
+            .filter(|weight| weight.abs() < threshold)
+            .map(|weight| *weight = F::default())
+            .count()
+    }
+
+    /// Snaps every weight to the nearest of `levels` evenly spaced values
+    /// spanning `range` (weights outside of it snap to its ends), returning
+    /// the mean absolute quantization error; biases are left untouched.
+    pub fn quantize(&mut self, levels: usize, range: (f32, f32)) -> f32 {
+        assert!(levels > 1);
+        assert!(range.0 < range.1);
+
+        let step = (range.1 - range.0) / (levels - 1) as f32;
+        let mut total_error = 0.0;
+        let mut count = 0;
+
+        let weights = self
+            .layers
+            .iter_mut()
+            .flat_map(|layer| layer.neurons.iter_mut())
+            .flat_map(|neuron| neuron.weights.iter_mut());
+
+        for weight in weights {
+            let level = ((weight.to_f32() - range.0) / step).round();
+            let quantized = range.0 + level.clamp(0.0, (levels - 1) as f32) * step;
+
+            total_error += (weight.to_f32() - quantized).abs();
+            count += 1;
+
+            *weight = F::from_f32(quantized);
+        }
+
+        if count == 0 {
+            0.0
+        } else {
+            total_error / count as f32
+        }
+    }
+}
+
+impl Network<f32> {
+    /// Converts all parameters to `f64`; this is lossless
+    pub fn to_f64(&self) -> Network<f64> {
+        self.map_params(|param| param as f64)
+    }
+}
+
+impl Network<f64> {
+    /// Converts all parameters to `f32`, rounding them to the nearest
+    /// representable values
+    pub fn to_f32(&self) -> Network<f32> {
+        self.map_params(|param| param as f32)
+    }
+}
+
+impl<F> Network<F> {
+    fn map_params<G>(&self, f: impl Fn(F) -> G + Copy) -> Network<G>
+    where
+        F: Copy,
+    {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| Layer {
+                neurons: layer
+                    .neurons
+                    .iter()
+                    .map(|neuron| Neuron {
+                        bias: f(neuron.bias),
+                        weights: neuron.weights.iter().map(|&weight| f(weight)).collect(),
+                        activation: neuron.activation,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Network { layers }
+    }
 }
 
-impl Layer {
+impl<F> Layer<F>
+where
+    F: Float,
+{
+    #[cfg(test)]
     pub fn random(
         rng: &mut dyn rand::RngCore,
         input_neurons: usize,
         output_neurons: usize,
+    ) -> Self {
+        Self::random_with_range(rng, input_neurons, output_neurons, -1.0..=1.0, -1.0..=1.0)
+    }
+
+    pub fn random_with_range(
+        rng: &mut dyn rand::RngCore,
+        input_neurons: usize,
+        output_neurons: usize,
+        bias_range: RangeInclusive<f32>,
+        weight_range: RangeInclusive<f32>,
     ) -> Self {
         let neurons = (0..output_neurons)
-            .map(|_| Neuron::random(rng, input_neurons))
+            .map(|_| {
+                Neuron::random_with_range(
+                    rng,
+                    input_neurons,
+                    bias_range.clone(),
+                    weight_range.clone(),
+                )
+            })
             .collect();
 
         Self { neurons }
     }
 
-    fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+    fn from_weights(
+        input_neurons: usize,
+        output_neurons: usize,
+        weights: &mut dyn Iterator<Item = F>,
+    ) -> Self {
+        let neurons = (0..output_neurons)
+            .map(|_| Neuron::from_weights(input_neurons, weights))
+            .collect();
+
+        Self { neurons }
+    }
+
+    fn propagate(&self, inputs: Vec<F>) -> Vec<F> {
         self.neurons
             .iter()
             .map(|neuron| neuron.propagate(&inputs))
@@ -60,25 +794,220 @@ impl Layer {
     }
 }
 
-impl Neuron {
+impl<F> Neuron<F>
+where
+    F: Float,
+{
+    #[cfg(test)]
     pub fn random(rng: &mut dyn rand::RngCore, output_size: usize) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
+        Self::random_with_range(rng, output_size, -1.0..=1.0, -1.0..=1.0)
+    }
+
+    pub fn random_with_range(
+        rng: &mut dyn rand::RngCore,
+        output_size: usize,
+        bias_range: RangeInclusive<f32>,
+        weight_range: RangeInclusive<f32>,
+    ) -> Self {
+        let bias = F::from_f32(rng.gen_range(bias_range));
 
         let weights = (0..output_size)
-            .map(|_| rng.gen_range(-1.0..=1.0))
+            .map(|_| F::from_f32(rng.gen_range(weight_range.clone())))
             .collect();
 
-        Self { bias, weights }
+        Self {
+            bias,
+            weights,
+            activation: Activation::default(),
+        }
+    }
+
+    fn from_weights(output_size: usize, weights: &mut dyn Iterator<Item = F>) -> Self {
+        let bias = weights.next().expect("got not enough weights");
+
+        let weights = (0..output_size)
+            .map(|_| weights.next().expect("got not enough weights"))
+            .collect();
+
+        Self {
+            bias,
+            weights,
+            activation: Activation::default(),
+        }
+    }
+
+    fn propagate(&self, inputs: &[F]) -> F {
+        let output = inputs
+            .iter()
+            .zip(&self.weights)
+            .map(|(&input, &weight)| input * weight)
+            .sum::<F>();
+
+        self.activation.apply(self.bias + output)
     }
+}
+
+impl Activation {
+    /// Slope of `LeakyRelu` to use when there's no better choice
+    pub const DEFAULT_LEAKY_RELU_SLOPE: f32 = 0.01;
+
+    /// Encodes the activation function as a discrete selector gene; note
+    /// that the slope of `LeakyRelu` is not encoded.
+    pub fn to_gene(&self) -> f32 {
+        match self {
+            Self::Relu => 0.0,
+            Self::LeakyRelu { .. } => 1.0,
+        }
+    }
+
+    /// Decodes activation function from a selector gene, rounding it to the
+    /// nearest variant; `LeakyRelu` gets given slope
+    pub fn from_gene(gene: f32, leaky_relu_slope: f32) -> Self {
+        if gene.round() >= 1.0 {
+            Self::LeakyRelu {
+                slope: leaky_relu_slope,
+            }
+        } else {
+            Self::Relu
+        }
+    }
+
+    pub fn apply<F>(&self, x: F) -> F
+    where
+        F: Float,
+    {
+        match *self {
+            Self::Relu => {
+                if x >= F::default() {
+                    x
+                } else {
+                    F::default()
+                }
+            }
+            Self::LeakyRelu { slope } => {
+                if x >= F::default() {
+                    x
+                } else {
+                    x * F::from_f32(slope)
+                }
+            }
+        }
+    }
+}
+
+/// Network with weights of each layer stored as a matrix, so that
+/// propagating boils down to a matrix-vector product per layer - which,
+/// for wide layers, is way faster than `Network`'s neuron-by-neuron loop.
+///
+/// Outputs match `Network::propagate()` up to rounding errors (products
+/// can get summed in a different order).
+#[cfg(feature = "ndarray")]
+#[derive(Clone, Debug)]
+pub struct MatrixNetwork<F = f32> {
+    layers: Vec<MatrixLayer<F>>,
+}
+
+#[cfg(feature = "ndarray")]
+#[derive(Clone, Debug)]
+struct MatrixLayer<F> {
+    /// One row per neuron, one column per input
+    weights: ndarray::Array2<F>,
+    biases: ndarray::Array1<F>,
+    activations: Vec<Activation>,
+}
+
+#[cfg(feature = "ndarray")]
+impl<F> MatrixNetwork<F>
+where
+    F: Float + ndarray::LinalgScalar,
+{
+    pub fn propagate(&self, inputs: Vec<F>) -> Vec<F> {
+        self.layers
+            .iter()
+            .fold(ndarray::Array1::from(inputs), |inputs, layer| {
+                let mut outputs = layer.weights.dot(&inputs) + &layer.biases;
+
+                for (output, activation) in outputs.iter_mut().zip(&layer.activations) {
+                    *output = activation.apply(*output);
+                }
+
+                outputs
+            })
+            .to_vec()
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<F> From<&Network<F>> for MatrixNetwork<F>
+where
+    F: Float + ndarray::LinalgScalar,
+{
+    fn from(network: &Network<F>) -> Self {
+        let layers = network
+            .layers
+            .iter()
+            .map(|layer| {
+                let neurons = &layer.neurons;
+                let inputs = neurons.first().map_or(0, |neuron| neuron.weights.len());
+
+                MatrixLayer {
+                    weights: ndarray::Array2::from_shape_fn(
+                        (neurons.len(), inputs),
+                        |(row, col)| neurons[row].weights[col],
+                    ),
+                    biases: neurons.iter().map(|neuron| neuron.bias).collect(),
+                    activations: neurons.iter().map(|neuron| neuron.activation).collect(),
+                }
+            })
+            .collect();
+
+        Self { layers }
+    }
+}
+
+/// Fitness function rewarding networks for how closely their weights
+/// match `target`'s - useful for checking whether (and how fast) evolution
+/// can find a known solution.
+#[derive(Debug)]
+pub struct TargetMatchFitness<F = f32> {
+    pub target: Network<F>,
+}
+
+impl<F> TargetMatchFitness<F>
+where
+    F: Float,
+{
+    pub fn new(target: Network<F>) -> Self {
+        Self { target }
+    }
+
+    /// Returns negated mean squared difference between weights of `network`
+    /// and the target; zero means a perfect match.
+    ///
+    /// Panics if the networks have different number of weights.
+    pub fn fitness(&self, network: &Network<F>) -> f32 {
+        let mut error = 0.0;
+        let mut count = 0;
+
+        let mut weights = network.weights();
+        let mut target = self.target.weights();
 
-    fn propagate(&self, inputs: &[f32]) -> f32 {
-        let output = inputs
-            .iter()
-            .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum::<f32>();
+        loop {
+            match (weights.next(), target.next()) {
+                (Some(weight), Some(target)) => {
+                    error += (weight.to_f32() - target.to_f32()).powi(2);
+                    count += 1;
+                }
+                (None, None) => break,
+                _ => panic!("network's shape doesn't match the target"),
+            }
+        }
 
-        (self.bias + output).max(0.0)
+        if count == 0 {
+            0.0
+        } else {
+            -error / count as f32
+        }
     }
 }
 
@@ -89,6 +1018,12 @@ mod neural_network {
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
 
+    // Float literals would otherwise make the tests below silently run in
+    // f64
+    type Network = super::Network<f32>;
+    type Layer = super::Layer<f32>;
+    type Neuron = super::Neuron<f32>;
+
     mod random {
         use super::*;
 
@@ -137,6 +1072,32 @@ mod neural_network {
                 [0.67383957, 0.8181262, 0.26284897].as_slice()
             );
         }
+
+        #[test]
+        fn with_range() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::random_with_range(
+                &mut rng,
+                &[
+                    LayerTopology { neurons: 3 },
+                    LayerTopology { neurons: 2 },
+                    LayerTopology { neurons: 1 },
+                ],
+                0.0..=0.0,
+                0.0..=0.0,
+            );
+
+            assert!(network.weights().all(|weight| weight == 0.0));
+        }
+
+        #[test]
+        fn neuron_with_range() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let neuron = Neuron::random_with_range(&mut rng, 100, 2.0..=2.0, 0.5..=0.75);
+
+            assert_eq!(neuron.bias, 2.0);
+            assert!(neuron.weights.iter().all(|w| (0.5..=0.75).contains(w)));
+        }
     }
 
     mod propagate {
@@ -147,6 +1108,7 @@ mod neural_network {
             let neuron = Neuron {
                 bias: 0.5,
                 weights: vec![-0.3, 0.8],
+                activation: Activation::Relu,
             };
 
             assert_relative_eq!(neuron.propagate(&[-10.0, -10.0]), 0.0,);
@@ -165,10 +1127,12 @@ mod neural_network {
                 Neuron {
                     bias: 0.0,
                     weights: vec![0.1, 0.2, 0.3],
+                    activation: Activation::Relu,
                 },
                 Neuron {
                     bias: 0.0,
                     weights: vec![0.4, 0.5, 0.6],
+                    activation: Activation::Relu,
                 },
             ];
 
@@ -192,10 +1156,12 @@ mod neural_network {
                         Neuron {
                             bias: 0.0,
                             weights: vec![-0.5, -0.4, -0.3],
+                            activation: Activation::Relu,
                         },
                         Neuron {
                             bias: 0.0,
                             weights: vec![-0.2, -0.1, 0.0],
+                            activation: Activation::Relu,
                         },
                     ],
                 },
@@ -203,6 +1169,7 @@ mod neural_network {
                     neurons: vec![Neuron {
                         bias: 0.0,
                         weights: vec![-0.5, 0.5],
+                        activation: Activation::Relu,
                     }],
                 },
             );
@@ -216,5 +1183,738 @@ mod neural_network {
 
             assert_relative_eq!(actual.as_slice(), expected.as_slice());
         }
+
+        #[test]
+        fn with_mask() {
+            let network = Network {
+                layers: vec![
+                    Layer {
+                        neurons: vec![
+                            Neuron {
+                                bias: 0.0,
+                                weights: vec![1.0, 0.0],
+                                activation: Activation::Relu,
+                            },
+                            Neuron {
+                                bias: 0.0,
+                                weights: vec![0.0, 1.0],
+                                activation: Activation::Relu,
+                            },
+                        ],
+                    },
+                    Layer {
+                        neurons: vec![Neuron {
+                            bias: 0.0,
+                            weights: vec![1.0, 2.0],
+                            activation: Activation::Relu,
+                        }],
+                    },
+                ],
+            };
+
+            let inputs = vec![0.5, 0.25];
+            let keep_all = [vec![true, true], vec![true]];
+            let drop_second = [vec![true, false], vec![true]];
+
+            assert_eq!(
+                network.propagate_with_mask(inputs.clone(), &keep_all),
+                network.propagate(inputs.clone())
+            );
+
+            // 0.5 * 1.0, with the 0.25 * 2.0 coming from the second neuron
+            // gone
+            for _ in 0..3 {
+                assert_eq!(
+                    network.propagate_with_mask(inputs.clone(), &drop_second),
+                    vec![0.5]
+                );
+            }
+        }
+
+        #[test]
+        fn infer() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology { neurons: 3 },
+                    LayerTopology { neurons: 4 },
+                    LayerTopology { neurons: 2 },
+                ],
+            );
+
+            let inputs = [0.5, -0.6, 0.7];
+            let expected = network.propagate(inputs.to_vec());
+
+            for _ in 0..3 {
+                assert_eq!(network.infer(&inputs), expected);
+            }
+        }
+
+        #[test]
+        fn trace() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology { neurons: 3 },
+                    LayerTopology { neurons: 2 },
+                    LayerTopology { neurons: 1 },
+                ],
+            );
+
+            let inputs = [0.5, -0.6, 0.7];
+            let trace = network.propagate_trace(&inputs);
+
+            assert_eq!(trace.len(), 2);
+            assert_eq!(trace[0].len(), 2);
+            assert_eq!(trace[1], network.propagate(inputs.to_vec()));
+        }
+    }
+
+    #[test]
+    fn weights() {
+        let network = Network {
+            layers: vec![
+                Layer {
+                    neurons: vec![Neuron {
+                        bias: 0.1,
+                        weights: vec![0.2, 0.3, 0.4],
+                        activation: Activation::Relu,
+                    }],
+                },
+                Layer {
+                    neurons: vec![Neuron {
+                        bias: 0.5,
+                        weights: vec![0.6],
+                        activation: Activation::Relu,
+                    }],
+                },
+            ],
+        };
+
+        let actual: Vec<_> = network.weights().collect();
+        let expected = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn weight_roles() {
+        let network = Network::from_weights(
+            &[LayerTopology { neurons: 2 }, LayerTopology { neurons: 2 }],
+            vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6],
+        );
+
+        let actual: Vec<_> = network.weight_roles().collect();
+
+        let expected = vec![
+            WeightRole::Bias,
+            WeightRole::Weight,
+            WeightRole::Weight,
+            WeightRole::Bias,
+            WeightRole::Weight,
+            WeightRole::Weight,
+        ];
+
+        assert_eq!(actual, expected);
+
+        let actual: Vec<_> = network.gene_roles().collect();
+
+        let expected = vec![
+            WeightRole::Activation,
+            WeightRole::Bias,
+            WeightRole::Weight,
+            WeightRole::Weight,
+            WeightRole::Activation,
+            WeightRole::Bias,
+            WeightRole::Weight,
+            WeightRole::Weight,
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn from_weights() {
+        let layers = &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }];
+        let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
+
+        let network = Network::from_weights(layers, weights.clone());
+        let actual: Vec<_> = network.weights().collect();
+
+        assert_relative_eq!(actual.as_slice(), weights.as_slice());
+    }
+
+    #[test]
+    fn weight_counts() {
+        let layers = &[
+            LayerTopology { neurons: 3 },
+            LayerTopology { neurons: 2 },
+            LayerTopology { neurons: 4 },
+        ];
+
+        let counts = LayerTopology::weight_counts(layers);
+        let network = Network::random(&mut ChaCha8Rng::from_seed(Default::default()), layers);
+
+        assert_eq!(counts, vec![2 * 4, 4 * 3]);
+        assert_eq!(counts.iter().sum::<usize>(), network.weights().count());
+
+        let counts = LayerTopology::gene_counts(layers);
+
+        assert_eq!(counts, vec![2 * 5, 4 * 4]);
+        assert_eq!(counts.iter().sum::<usize>(), network.genes().count());
+    }
+
+    #[test]
+    fn prune() {
+        let mut network = Network {
+            layers: vec![
+                Layer {
+                    neurons: vec![
+                        Neuron {
+                            bias: 0.01,
+                            weights: vec![0.05, -0.5, -0.02],
+                            activation: Activation::Relu,
+                        },
+                        Neuron {
+                            bias: 0.0,
+                            weights: vec![0.3, 0.09, -0.1],
+                            activation: Activation::Relu,
+                        },
+                    ],
+                },
+                Layer {
+                    neurons: vec![Neuron {
+                        bias: -0.05,
+                        weights: vec![-0.08, 0.5],
+                        activation: Activation::Relu,
+                    }],
+                },
+            ],
+        };
+
+        assert_eq!(network.prune(0.1), 4);
+
+        assert_relative_eq!(network.layers[0].neurons[0].bias, 0.01);
+        assert_relative_eq!(
+            network.layers[0].neurons[0].weights.as_slice(),
+            [0.0, -0.5, 0.0].as_slice()
+        );
+        assert_relative_eq!(
+            network.layers[0].neurons[1].weights.as_slice(),
+            [0.3, 0.0, -0.1].as_slice()
+        );
+        assert_relative_eq!(network.layers[1].neurons[0].bias, -0.05);
+        assert_relative_eq!(
+            network.layers[1].neurons[0].weights.as_slice(),
+            [0.0, 0.5].as_slice()
+        );
+
+        assert_eq!(network.propagate(vec![0.5, 0.6, 0.7]).len(), 1);
+
+        // Already-zero weights are still below the threshold
+        assert_eq!(network.prune(0.1), 4);
+        assert_eq!(network.prune(0.2), 5);
+    }
+
+    #[test]
+    fn quantize() {
+        let mut network = Network {
+            layers: vec![Layer {
+                neurons: vec![
+                    Neuron {
+                        bias: 0.3,
+                        weights: vec![-0.8, 0.2, 1.5],
+                        activation: Activation::Relu,
+                    },
+                    Neuron {
+                        bias: 0.0,
+                        weights: vec![-0.1, 0.6, -2.0],
+                        activation: Activation::Relu,
+                    },
+                ],
+            }],
+        };
+
+        let error = network.quantize(2, (-1.0, 1.0));
+
+        assert_relative_eq!(
+            network.layers[0].neurons[0].weights.as_slice(),
+            [-1.0, 1.0, 1.0].as_slice()
+        );
+
+        assert_relative_eq!(
+            network.layers[0].neurons[1].weights.as_slice(),
+            [-1.0, 1.0, -1.0].as_slice()
+        );
+
+        assert_relative_eq!(network.layers[0].neurons[0].bias, 0.3);
+        assert_relative_eq!(
+            error,
+            (0.2 + 0.8 + 0.5 + 0.9 + 0.4 + 1.0) / 6.0,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn dead_neuron_count() {
+        let neuron = |weights: Vec<f32>| Neuron {
+            bias: -1.0,
+            weights,
+            activation: Activation::Relu,
+        };
+
+        let network = Network {
+            layers: vec![
+                Layer {
+                    neurons: vec![neuron(vec![0.1, 0.1]), neuron(vec![0.2, -0.2])],
+                },
+                Layer {
+                    neurons: vec![neuron(vec![0.5, 0.5])],
+                },
+            ],
+        };
+
+        let samples = vec![vec![0.0, 0.0], vec![1.0, 1.0], vec![-1.0, 2.0]];
+
+        assert_eq!(network.dead_neuron_count(&samples), 3);
+
+        let network = network.with_activation(Activation::LeakyRelu { slope: 0.01 });
+
+        assert_eq!(network.dead_neuron_count(&samples), 0);
+    }
+
+    #[test]
+    fn flops() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology { neurons: 3 },
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 1 },
+            ],
+        );
+
+        // 3*2 + 2*1
+        assert_eq!(network.flops(), 8);
+    }
+
+    #[test]
+    fn f64_precision() {
+        const INPUTS: usize = 1000;
+
+        let layers = &[
+            LayerTopology { neurons: INPUTS },
+            LayerTopology { neurons: 1 },
+        ];
+
+        // bias, followed by a weight per input
+        let actual_f32 = Network::from_weights(layers, once(0.0).chain(vec![0.1; INPUTS]))
+            .propagate(vec![0.1; INPUTS])[0] as f64;
+
+        let actual_f64 =
+            super::Network::<f64>::from_weights(layers, once(0.0).chain(vec![0.1; INPUTS]))
+                .propagate(vec![0.1; INPUTS])[0];
+
+        // 1000 * (0.1 * 0.1)
+        let expected = 10.0;
+
+        assert!((actual_f64 - expected).abs() < (actual_f32 - expected).abs());
+        assert_relative_eq!(actual_f64, expected, epsilon = 1e-9);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn matrix_network() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology { neurons: 50 },
+                LayerTopology { neurons: 30 },
+                LayerTopology { neurons: 10 },
+            ],
+        )
+        .with_activation(Activation::LeakyRelu { slope: 0.01 });
+
+        let matrix = MatrixNetwork::from(&network);
+
+        for _ in 0..10 {
+            let inputs: Vec<f32> = (0..50).map(|_| rng.gen_range(-1.0..=1.0)).collect();
+
+            let expected = network.propagate(inputs.clone());
+            let actual = matrix.propagate(inputs);
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn precision_conversion() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology { neurons: 3 },
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 1 },
+            ],
+        )
+        .with_activation(Activation::LeakyRelu { slope: 0.1 });
+
+        let network_f64 = network.to_f64();
+
+        assert_eq!(
+            network_f64.weights().collect::<Vec<_>>(),
+            network.weights().map(|w| w as f64).collect::<Vec<_>>()
+        );
+
+        let round_tripped = network_f64.to_f32();
+
+        assert_eq!(
+            round_tripped.weights().collect::<Vec<_>>(),
+            network.weights().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            round_tripped.layers[0].neurons[0].activation,
+            Activation::LeakyRelu { slope: 0.1 }
+        );
+
+        let inputs = vec![0.5, -0.6, 0.7];
+
+        assert_relative_eq!(
+            network_f64.propagate(inputs.iter().map(|&i| i as f64).collect())[0],
+            network.propagate(inputs)[0] as f64,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn maximize_output() {
+        let network = Network::from_weights(
+            &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 1 }],
+            vec![0.1, 0.2, 0.9, 0.1],
+        );
+
+        let inputs = network.maximize_output(0, 20, 0.1);
+
+        assert!(inputs[1] > inputs[0]);
+        assert!(inputs[1] > inputs[2]);
+    }
+
+    #[test]
+    fn effective_params() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 1 },
+            ],
+            vec![
+                0.1, 0.5, 0.5, // hidden neuron #1
+                0.1, 0.3, 0.3, // hidden neuron #2
+                0.0, 1.0, 0.0, // output neuron, ignoring hidden neuron #2
+            ],
+        );
+
+        let samples = vec![vec![1.0, 1.0], vec![0.5, 0.2]];
+
+        assert_eq!(network.effective_params(&samples, 0.01), 6);
+
+        let network = Network::from_weights(
+            &[
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 1 },
+            ],
+            vec![
+                0.1, 0.5, 0.5, // hidden neuron #1
+                0.1, 0.3, 0.3, // hidden neuron #2
+                0.0, 1.0, 1e-5, // output neuron, barely listening to hidden neuron #2
+            ],
+        );
+
+        // Nudging hidden neuron #2 changes the output by at most 1e-7, which
+        // is below the 1e-4 (`epsilon²`) threshold
+        assert_eq!(network.effective_params(&samples, 0.01), 6);
+    }
+
+    #[test]
+    fn weight_histogram() {
+        let network = Network::from_weights(
+            &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }],
+            vec![
+                5.0, -0.9, -0.1, 0.1, // first neuron
+                -5.0, 0.6, 2.0, -2.0, // second neuron
+            ],
+        );
+
+        let actual = network.weight_histogram(4, (-1.0, 1.0));
+
+        assert_eq!(actual, vec![2, 1, 1, 2]);
+    }
+
+    #[test]
+    fn input_influence() {
+        let network = Network::from_weights(
+            &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 1 }],
+            vec![0.0, 2.0, 0.1, 0.1],
+        );
+
+        let samples = vec![vec![1.0, 1.0, 1.0], vec![0.5, 0.2, 0.8]];
+        let actual = network.input_influence(&samples);
+
+        // first input: (2.0 * 1.0 + 2.0 * 0.5) / 2
+        assert_relative_eq!(actual[0], 1.5);
+        assert!(actual[0] > actual[1]);
+        assert!(actual[0] > actual[2]);
+    }
+
+    #[test]
+    fn behavioral_distance() {
+        let topology = [
+            LayerTopology { neurons: 2 },
+            LayerTopology { neurons: 2 },
+            LayerTopology { neurons: 1 },
+        ];
+
+        let a = Network::from_weights(
+            &topology,
+            vec![0.1, 0.5, -0.3, 0.2, -0.4, 0.6, 0.0, 1.0, 2.0],
+        );
+
+        // Same as `a`, just with hidden neurons swapped
+        let b = Network::from_weights(
+            &topology,
+            vec![0.2, -0.4, 0.6, 0.1, 0.5, -0.3, 0.0, 2.0, 1.0],
+        );
+
+        let c = Network::from_weights(&topology, vec![0.0; 9]);
+
+        let probes = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.5, -0.7]];
+
+        assert_ne!(
+            a.weights().collect::<Vec<_>>(),
+            b.weights().collect::<Vec<_>>()
+        );
+        assert_relative_eq!(a.behavioral_distance(&b, &probes), 0.0, epsilon = 1e-6);
+        assert!(a.behavioral_distance(&c, &probes) > 0.1);
+    }
+
+    #[test]
+    fn to_dot() {
+        let network = Network::from_weights(
+            &[LayerTopology { neurons: 2 }, LayerTopology { neurons: 1 }],
+            vec![0.1, 0.2, -0.3],
+        );
+
+        let dot = network.to_dot();
+
+        assert!(dot.starts_with("digraph network {"));
+        assert!(dot.contains("    i0;\n"));
+        assert!(dot.contains("    i1;\n"));
+        assert!(dot.contains("    n0_0 [label=\"0.1\"];\n"));
+        assert!(dot.contains("    i0 -> n0_0 [label=\"0.2\"];\n"));
+        assert!(dot.contains("    i1 -> n0_0 [label=\"-0.3\"];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn genes() {
+        let layers = &[LayerTopology { neurons: 2 }, LayerTopology { neurons: 2 }];
+
+        let mut network = Network::from_weights(layers, vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+        network.layers[0].neurons[1].activation = Activation::LeakyRelu { slope: 0.2 };
+
+        let genes: Vec<_> = network.genes().collect();
+
+        assert_relative_eq!(
+            genes.as_slice(),
+            [0.0, 0.1, 0.2, 0.3, 1.0, 0.4, 0.5, 0.6].as_slice()
+        );
+
+        let restored = Network::from_genes(layers, genes, 0.2);
+
+        assert_eq!(
+            restored.activations().collect::<Vec<_>>(),
+            vec![Activation::Relu, Activation::LeakyRelu { slope: 0.2 }]
+        );
+
+        let actual: Vec<_> = restored.weights().collect();
+        let expected: Vec<_> = network.weights().collect();
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn validate() {
+        let layers = &[
+            LayerTopology { neurons: 3 },
+            LayerTopology { neurons: 2 },
+            LayerTopology { neurons: 1 },
+        ];
+
+        let mut network = Network::from_weights(layers, vec![0.1; 11]);
+
+        assert_eq!(network.validate(), Ok(()));
+
+        network.layers[1].neurons[0].weights.push(0.1);
+
+        let err = network.validate().unwrap_err();
+
+        assert_eq!(
+            err,
+            NetworkError::InconsistentWeights {
+                layer: 1,
+                neuron: 0,
+                expected: 2,
+                actual: 3,
+            }
+        );
+
+        assert_eq!(
+            err.to_string(),
+            "neuron #0 in layer #1 has 3 weights, but the preceding layer has 2 neurons"
+        );
+    }
+
+    mod stack {
+        use super::*;
+
+        fn network(rng: &mut dyn RngCore, layers: &[usize]) -> Network {
+            let layers: Vec<_> = layers
+                .iter()
+                .map(|&neurons| LayerTopology { neurons })
+                .collect();
+
+            Network::random(rng, &layers)
+        }
+
+        #[test]
+        fn matching_sizes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let a = network(&mut rng, &[3, 2]);
+            let b = network(&mut rng, &[2, 1]);
+
+            let inputs = vec![0.5, -0.6, 0.7];
+            let expected = b.propagate(a.propagate(inputs.clone()));
+
+            let stacked = a.stack(b).unwrap();
+
+            assert_eq!(stacked.layers.len(), 2);
+            assert_eq!(stacked.propagate(inputs), expected);
+        }
+
+        #[test]
+        fn mismatched_sizes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let a = network(&mut rng, &[3, 2]);
+            let b = network(&mut rng, &[3, 1]);
+
+            assert_eq!(
+                a.stack(b).unwrap_err(),
+                NetworkError::SizeMismatch {
+                    outputs: 2,
+                    inputs: 3
+                }
+            );
+        }
+    }
+
+    mod target_match_fitness {
+        use super::*;
+        use lib_genetic_algorithm as ga;
+
+        fn topology() -> [LayerTopology; 3] {
+            [
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 1 },
+            ]
+        }
+
+        fn fitness() -> TargetMatchFitness {
+            let target = (0..9).map(|idx| idx as f32 / 10.0 - 0.4);
+
+            TargetMatchFitness::new(Network::from_weights(&topology(), target))
+        }
+
+        #[derive(Clone, Debug)]
+        struct Candidate {
+            chromosome: ga::Chromosome,
+            fitness: f32,
+        }
+
+        impl ga::Individual for Candidate {
+            fn create(chromosome: ga::Chromosome) -> Self {
+                let network = Network::from_weights(&topology(), chromosome.iter().copied());
+                let fitness = fitness().fitness(&network);
+
+                Self {
+                    chromosome,
+                    fitness,
+                }
+            }
+
+            fn chromosome(&self) -> &ga::Chromosome {
+                &self.chromosome
+            }
+
+            fn fitness(&self) -> f32 {
+                self.fitness
+            }
+        }
+
+        #[test]
+        fn perfect_match() {
+            let target = fitness().target;
+
+            assert_eq!(fitness().fitness(&target), 0.0);
+        }
+
+        #[test]
+        fn mismatch() {
+            let network =
+                Network::from_weights(&topology(), fitness().target.weights().map(|w| w + 0.5));
+
+            assert_relative_eq!(fitness().fitness(&network), -0.25);
+        }
+
+        #[test]
+        fn evolution_converges() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let ga = ga::GeneticAlgorithm::new(
+                ga::TournamentSelection::new(2),
+                ga::UniformCrossover::new(),
+                ga::GaussianMutation::new(0.3, 0.1),
+            )
+            .with_elitism(2);
+
+            let mut population: Vec<_> = (0..50)
+                .map(|_| {
+                    let network = Network::random(&mut rng, &topology());
+                    <Candidate as ga::Individual>::create(network.weights().collect())
+                })
+                .collect();
+
+            let best = |population: &[Candidate]| ga::best_individual(population).fitness;
+            let initial = best(&population);
+
+            for _ in 0..100 {
+                population = ga.evolve(&mut rng, &population);
+            }
+
+            let evolved = best(&population);
+
+            assert!(evolved > initial, "{} <= {}", evolved, initial);
+            assert!(evolved > -0.01, "{}", evolved);
+        }
     }
 }
@@ -0,0 +1,95 @@
+use crate::*;
+
+/// Describes how brain outputs get turned into a discrete action, for
+/// locomotions that choose between a fixed set of them
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ActionSelection {
+    /// Always picks the action with the highest output
+    #[default]
+    Argmax,
+
+    /// Treats outputs as logits and samples an action from their softmax
+    /// distribution
+    Sample
+}
+
+impl ActionSelection {
+    crate fn select(&self, logits: &[f32], rng: &mut dyn RngCore) -> usize {
+        assert!(!logits.is_empty(), "Cannot select an action out of no outputs");
+
+        match self {
+            Self::Argmax => logits
+                .iter()
+                .enumerate()
+                .fold(0, |best, (idx, logit)| if *logit > logits[best] { idx } else { best }),
+
+            Self::Sample => {
+                // Subtracting the maximum keeps `exp()` from overflowing
+                let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                let weights: Vec<_> = logits.iter().map(|logit| (logit - max).exp()).collect();
+                let total: f32 = weights.iter().sum();
+
+                let mut roll = rng.gen_range(0.0..total);
+
+                for (idx, weight) in weights.iter().enumerate() {
+                    if roll < *weight {
+                        return idx;
+                    }
+
+                    roll -= weight;
+                }
+
+                weights.len() - 1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn argmax_always_chooses_top_logit() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for _ in 0..100 {
+            assert_eq!(ActionSelection::Argmax.select(&[0.1, 2.0, -1.0], &mut rng), 1);
+        }
+    }
+
+    #[test]
+    fn argmax_prefers_first_on_ties() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        assert_eq!(ActionSelection::Argmax.select(&[0.5, 1.0, 1.0], &mut rng), 1);
+    }
+
+    #[test]
+    fn sample_respects_seed() {
+        let sample = |seed| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+            (0..50)
+                .map(|_| ActionSelection::Sample.select(&[0.0, 0.5, 1.0], &mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sample(1), sample(1));
+        assert_ne!(sample(1), sample(2));
+    }
+
+    #[test]
+    fn sample_follows_softmax() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut counts = [0; 2];
+
+        for _ in 0..1000 {
+            counts[ActionSelection::Sample.select(&[0.0, 2.0_f32.ln()], &mut rng)] += 1;
+        }
+
+        // Expected ratio is 1:2
+        assert!((300..370).contains(&counts[0]), "{:?}", counts);
+    }
+}
@@ -7,36 +7,123 @@ pub struct Animal {
     crate rotation: na::Rotation2<f32>,
     crate speed: f32,
     crate eye: Eye,
-    crate brain: nn::Network
+    crate brain: nn::Network,
+    crate satiation: f32,
+
+    /// Length of the path this animal has traveled so far
+    crate distance_traveled: f32
 }
 
 impl Animal {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-
-        let brain = nn::Network::random(
-            rng, 
-            &[
-                nn::LayerTopology { neurons: eye.cells() },
-                nn::LayerTopology { neurons: 2 * eye.cells() },
-                nn::LayerTopology { neurons: 2 }
-            ]
-        );
-        
+    pub fn random(config: &SimulationConfig, rng: &mut dyn RngCore) -> Self {
+        let eye = Self::eye(config);
+        let brain = nn::Network::random(rng, &Self::topology(config, &eye))
+            .with_activation(config.brain_activation);
+
+        Self::new(config, eye, brain, rng)
+    }
+
+    crate fn from_chromosome(
+        config: &SimulationConfig,
+        chromosome: ga::Chromosome,
+        rng: &mut dyn RngCore
+    ) -> Self {
+        let eye = Self::eye(config);
+
+        // Activations come from the chromosome, so that they can be evolved
+        // per neuron; only the slope is taken from the config
+        let leaky_relu_slope = match config.brain_activation {
+            nn::Activation::LeakyRelu { slope } => slope,
+            nn::Activation::Relu => nn::Activation::DEFAULT_LEAKY_RELU_SLOPE
+        };
+
+        let brain =
+            nn::Network::from_genes(&Self::topology(config, &eye), chromosome, leaky_relu_slope);
+
+        Self::new(config, eye, brain, rng)
+    }
+
+    crate fn as_chromosome(&self) -> ga::Chromosome {
+        self.brain.genes().collect()
+    }
+
+    pub fn position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    pub fn rotation(&self) -> na::Rotation2<f32> {
+        self.rotation
+    }
+
+    fn new(
+        config: &SimulationConfig,
+        eye: Eye,
+        brain: nn::Network,
+        rng: &mut dyn RngCore
+    ) -> Self {
         Self {
             position: rng.gen(),
             rotation: rng.gen(),
-            speed: 0.002,
+            speed: config.initial_speed,
             eye,
-            brain
+            brain,
+            satiation: 0.0,
+            distance_traveled: 0.0
         }
     }
 
-    pub fn position(&self) -> na::Point2<f32> {
-        self.position
+    fn eye(config: &SimulationConfig) -> Eye {
+        Eye::default()
+            .with_wrap(config.wrap_vision)
+            .with_smoothing(config.vision_smoothing)
     }
 
-    pub fn rotation(&self) -> na::Rotation2<f32> {
-        self.rotation
+    crate fn topology(config: &SimulationConfig, eye: &Eye) -> [nn::LayerTopology; 3] {
+        [
+            nn::LayerTopology { neurons: eye.cells() },
+            nn::LayerTopology { neurons: 2 * eye.cells() },
+            nn::LayerTopology { neurons: config.brain_outputs }
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn configurable_brain_outputs() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            brain_outputs: 3,
+            ..Default::default()
+        };
+
+        let animal = Animal::random(&config, &mut rng);
+        let response = animal.brain.propagate(vec![0.0; animal.eye.cells()]);
+
+        assert_eq!(response.len(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn chromosome_carries_activations() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let config = SimulationConfig::default();
+
+        let mut chromosome: Vec<_> = Animal::random(&config, &mut rng)
+            .as_chromosome()
+            .into_iter()
+            .collect();
+
+        // Switches the first neuron from `LeakyRelu` (as configured) to `Relu`
+        assert_eq!(chromosome[0], nn::Activation::LeakyRelu { slope: 0.01 }.to_gene());
+        chromosome[0] = nn::Activation::Relu.to_gene();
+
+        let animal =
+            Animal::from_chromosome(&config, chromosome.iter().copied().collect(), &mut rng);
+
+        assert!(animal.as_chromosome().iter().eq(chromosome.iter()));
+    }
+}
@@ -5,16 +5,56 @@ pub struct AnimalIndividual {
     chromosome: ga::Chromosome
 }
 
+impl AnimalIndividual {
+    pub fn from_animal(animal: &Animal) -> Self {
+        Self {
+            fitness: animal.satiation,
+            chromosome: animal.as_chromosome()
+        }
+    }
+
+    pub fn into_animal(self, config: &SimulationConfig, rng: &mut dyn RngCore) -> Animal {
+        Animal::from_chromosome(config, self.chromosome, rng)
+    }
+}
+
 impl ga::Individual for AnimalIndividual {
     fn create(chromosome: ga::Chromosome) -> Self {
-        todo!()
+        Self {
+            fitness: 0.0,
+            chromosome
+        }
     }
 
     fn chromosome(&self) -> &ga::Chromosome {
-        todo!()
+        &self.chromosome
     }
 
     fn fitness(&self) -> f32 {
-        todo!()
+        self.fitness
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn random_population() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let config = SimulationConfig::default();
+
+        let population = ga::Population::random(10, &mut rng, |rng| {
+            AnimalIndividual::from_animal(&Animal::random(&config, rng))
+        });
+
+        assert_eq!(population.len(), 10);
+
+        for (idx, a) in population.iter().enumerate() {
+            for b in &population[idx + 1..] {
+                assert!(a.chromosome.iter().ne(b.chromosome.iter()));
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::*;
+use std::f32::consts::PI;
+
+/// Describes what happens to animals reaching the edge of the world
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Boundary {
+    /// Animals leaving through one edge come back through the opposite one
+    #[default]
+    Wrap,
+
+    /// Animals bounce off the edges, as if they were walls
+    Reflect,
+
+    /// Animals stop at the edges, keeping their rotation
+    Clamp
+}
+
+impl Boundary {
+    crate fn apply(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>
+    ) -> (na::Point2<f32>, na::Rotation2<f32>) {
+        match self {
+            Self::Wrap => (
+                na::Point2::new(na::wrap(position.x, 0.0, 1.0), na::wrap(position.y, 0.0, 1.0)),
+                rotation
+            ),
+
+            Self::Reflect => {
+                let mut position = position;
+                let mut angle = rotation.angle();
+
+                if !(0.0..=1.0).contains(&position.x) {
+                    position.x = Self::mirror(position.x);
+                    angle = PI - angle;
+                }
+
+                if !(0.0..=1.0).contains(&position.y) {
+                    position.y = Self::mirror(position.y);
+                    angle = -angle;
+                }
+
+                (position, na::Rotation2::new(angle))
+            }
+
+            Self::Clamp => (
+                na::Point2::new(position.x.clamp(0.0, 1.0), position.y.clamp(0.0, 1.0)),
+                rotation
+            )
+        }
+    }
+
+    /// Mirrors coordinate that's outside of the world back inside of it
+    fn mirror(value: f32) -> f32 {
+        if value < 0.0 {
+            -value
+        } else {
+            2.0 - value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Animal that has just crossed the right wall, heading right
+    fn heading_into_right_wall(boundary: Boundary) -> (na::Point2<f32>, na::Vector2<f32>) {
+        let (position, rotation) =
+            boundary.apply(na::Point2::new(1.02, 0.5), na::Rotation2::new(0.0));
+
+        (position, rotation * na::Vector2::x())
+    }
+
+    #[test]
+    fn wrap() {
+        let (position, heading) = heading_into_right_wall(Boundary::Wrap);
+
+        assert_relative_eq!(position.x, 0.02, epsilon = 1e-6);
+        assert_relative_eq!(position.y, 0.5);
+        assert_relative_eq!(heading.x, 1.0);
+    }
+
+    #[test]
+    fn reflect() {
+        let (position, heading) = heading_into_right_wall(Boundary::Reflect);
+
+        assert_relative_eq!(position.x, 0.98, epsilon = 1e-6);
+        assert_relative_eq!(position.y, 0.5);
+        assert_relative_eq!(heading.x, -1.0);
+        assert_relative_eq!(heading.y, 0.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn clamp() {
+        let (position, heading) = heading_into_right_wall(Boundary::Clamp);
+
+        assert_relative_eq!(position.x, 1.0);
+        assert_relative_eq!(position.y, 0.5);
+        assert_relative_eq!(heading.x, 1.0);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::*;
+
+/// Returns center and radius of the smallest circle containing all of
+/// `points` (using Welzl's algorithm, in its iterative form); for no points
+/// returns a zero-radius circle at the origin
+crate fn bounding_circle(points: &[na::Point2<f32>]) -> (na::Point2<f32>, f32) {
+    let mut circle = match points.first() {
+        Some(&point) => (point, 0.0),
+        None => return (na::Point2::origin(), 0.0)
+    };
+
+    for (i, &p) in points.iter().enumerate() {
+        if contains(circle, p) {
+            continue;
+        }
+
+        circle = (p, 0.0);
+
+        for (j, &q) in points[..i].iter().enumerate() {
+            if contains(circle, q) {
+                continue;
+            }
+
+            circle = diametral(p, q);
+
+            for &r in &points[..j] {
+                if !contains(circle, r) {
+                    circle = circumscribed(p, q, r);
+                }
+            }
+        }
+    }
+
+    circle
+}
+
+fn contains((center, radius): (na::Point2<f32>, f32), point: na::Point2<f32>) -> bool {
+    na::distance(&center, &point) <= radius * (1.0 + 1e-5) + 1e-6
+}
+
+fn diametral(a: na::Point2<f32>, b: na::Point2<f32>) -> (na::Point2<f32>, f32) {
+    (na::center(&a, &b), na::distance(&a, &b) / 2.0)
+}
+
+fn circumscribed(
+    a: na::Point2<f32>,
+    b: na::Point2<f32>,
+    c: na::Point2<f32>
+) -> (na::Point2<f32>, f32) {
+    let ab = b - a;
+    let ac = c - a;
+    let d = 2.0 * (ab.x * ac.y - ab.y * ac.x);
+
+    // Collinear points - the circle spans the two farthest ones
+    if d.abs() < f32::EPSILON {
+        return [diametral(a, b), diametral(a, c), diametral(b, c)]
+            .into_iter()
+            .fold((a, 0.0), |best, circle| if circle.1 > best.1 { circle } else { best });
+    }
+
+    let offset = na::Vector2::new(
+        ac.y * ab.norm_squared() - ab.y * ac.norm_squared(),
+        ab.x * ac.norm_squared() - ac.x * ab.norm_squared()
+    ) / d;
+
+    (a + offset, offset.norm())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collinear() {
+        let (center, radius) = bounding_circle(&[
+            na::Point2::new(0.2, 0.5),
+            na::Point2::new(0.4, 0.5),
+            na::Point2::new(0.8, 0.5)
+        ]);
+
+        approx::assert_relative_eq!(center, na::Point2::new(0.5, 0.5));
+        approx::assert_relative_eq!(radius, 0.3);
+    }
+
+    #[test]
+    fn triangle() {
+        // Acute triangle, so its circumcircle is the answer
+        let (center, radius) = bounding_circle(&[
+            na::Point2::new(0.0, 0.0),
+            na::Point2::new(1.0, 0.0),
+            na::Point2::new(0.5, 0.8),
+            na::Point2::new(0.5, 0.3)
+        ]);
+
+        let expected_y = (0.64 - 0.25) / 1.6;
+
+        approx::assert_relative_eq!(center, na::Point2::new(0.5, expected_y), epsilon = 1e-6);
+        approx::assert_relative_eq!(radius, 0.8 - expected_y, epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::*;
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    pub world_animals: usize,
+    pub world_foods: usize,
+
+    pub generation_length: GenerationLength,
+
+    pub locomotion: Locomotion,
+
+    /// How `Locomotion::Discrete` picks an action out of brain outputs
+    pub action_selection: ActionSelection,
+
+    pub boundary: Boundary,
+
+    /// Whether animals see foods across the edges of the world; makes
+    /// sense mostly together with `Boundary::Wrap`
+    pub wrap_vision: bool,
+
+    /// Standard deviation (in eye cells) of the Gaussian kernel smoothing
+    /// vision across neighbouring cells; zero disables the smoothing
+    pub vision_smoothing: f32,
+
+    /// Number of neurons in brain's output layer; must be enough to drive
+    /// the chosen `locomotion`
+    pub brain_outputs: usize,
+
+    /// Activation function brains of the initial population start with;
+    /// afterwards it's evolved per neuron, with `LeakyRelu` neurons keeping
+    /// the slope given here (or `nn::Activation::DEFAULT_LEAKY_RELU_SLOPE`,
+    /// when starting with `Relu`)
+    pub brain_activation: nn::Activation,
+
+    /// Standard deviation of Gaussian noise added to each eye cell before
+    /// it's fed into the brain; zero disables the noise
+    pub vision_noise: f32,
+
+    /// Speed each animal starts with
+    pub initial_speed: f32,
+
+    /// Range animal's speed is clamped into, so that animals can't freeze in
+    /// place; for `Locomotion::DifferentialDrive`, which can drive backwards,
+    /// it's the range of speed's magnitude instead (i.e. speed can also be
+    /// anywhere within `-speed_max..=-speed_min`)
+    pub speed_min: f32,
+    pub speed_max: f32,
+
+    /// Maximum change of speed in a single step
+    pub speed_accel: f32,
+
+    /// Range of values (i.e. satiation gained by eating) foods get spawned
+    /// with
+    pub food_value_min: f32,
+    pub food_value_max: f32,
+
+    pub food_spawn: FoodSpawn,
+
+    /// Number of fresh foods added at the end of each generation, with
+    /// the remaining ones carried over into the next one; zero means foods
+    /// get entirely replaced instead.
+    ///
+    /// When non-zero, foods are never respawned - so e.g. clusters of
+    /// `FoodSpawn::Clustered` stay where they were initially placed, and with
+    /// `GenerationLength::UntilAllEaten` each generation after the first one
+    /// starts with just `food_replenish_per_generation` foods (rather than
+    /// `world_foods`).
+    pub food_replenish_per_generation: usize,
+
+    /// Cap on the number of foods for `food_replenish_per_generation`
+    pub food_max: Option<usize>,
+
+    /// Duration of a single step - animals move `speed * dt` per step, so
+    /// e.g. halving `dt` and doubling the number of steps yields the same
+    /// distance traveled at a finer temporal resolution
+    pub dt: f32
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            world_animals: 40,
+            world_foods: 60,
+            generation_length: GenerationLength::default(),
+            locomotion: Locomotion::default(),
+            action_selection: ActionSelection::default(),
+            boundary: Boundary::default(),
+            wrap_vision: false,
+            vision_smoothing: 0.0,
+            brain_outputs: 2,
+            brain_activation: nn::Activation::LeakyRelu { slope: 0.01 },
+            vision_noise: 0.0,
+            initial_speed: 0.002,
+            speed_min: 0.001,
+            speed_max: 0.005,
+            speed_accel: 0.2,
+            food_value_min: 1.0,
+            food_value_max: 1.0,
+            food_spawn: FoodSpawn::default(),
+            food_replenish_per_generation: 0,
+            food_max: None,
+            dt: 1.0
+        }
+    }
+}
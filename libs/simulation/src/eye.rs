@@ -9,7 +9,20 @@ const CELLS: usize = 9;
 pub struct Eye {
     fov_range: f32,
     fov_angle: f32,
-    cells: usize
+    cells: usize,
+
+    /// When enabled, each cell only sees the nearest food within it
+    /// instead of the sum of all of them
+    occlusion: bool,
+
+    /// When enabled, foods are seen across the edges of the world, as if
+    /// it was a torus (matching `Boundary::Wrap`)
+    wrap: bool,
+
+    /// Standard deviation (in cells) of the Gaussian kernel each food gets
+    /// spread with across the cells, so that foods near a boundary activate
+    /// both the neighbouring cells; zero keeps the hard binning
+    smoothing: f32
 }
 
 impl Eye {
@@ -18,7 +31,36 @@ impl Eye {
         assert!(fov_angle > 0.0);
         assert!(cells > 0);
 
-        Self { fov_range, fov_angle, cells }
+        Self { fov_range, fov_angle, cells, occlusion: false, wrap: false, smoothing: 0.0 }
+    }
+
+    /// Creates an eye with as many cells as needed for each of them to
+    /// span (at most) `degrees_per_cell`; `fov_angle` is given in radians.
+    pub fn with_angular_resolution(fov_angle: f32, fov_range: f32, degrees_per_cell: f32) -> Self {
+        assert!(degrees_per_cell > 0.0);
+
+        // Tolerance keeps e.g. 90° / 10° from becoming 10 cells due to
+        // rounding errors of the radians-to-degrees conversion
+        let cells = (fov_angle.to_degrees() / degrees_per_cell - 1e-4).ceil() as usize;
+
+        Self::new(fov_range, fov_angle, cells.max(1))
+    }
+
+    pub fn with_occlusion(mut self, occlusion: bool) -> Self {
+        self.occlusion = occlusion;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        assert!(smoothing >= 0.0);
+
+        self.smoothing = smoothing;
+        self
     }
 
     pub fn cells(&self) -> usize {
@@ -34,7 +76,14 @@ impl Eye {
         let mut cells = vec![0.0; self.cells];
 
         for food in foods {
-            let vec = food.position - position;
+            let mut vec = food.position - position;
+
+            if self.wrap {
+                // Shortest offset on a torus never exceeds half the world
+                vec.x = na::wrap(vec.x, -0.5, 0.5);
+                vec.y = na::wrap(vec.y, -0.5, 0.5);
+            }
+
             let dist = vec.norm();
             
             if dist >= self.fov_range {
@@ -56,13 +105,45 @@ impl Eye {
             let angle = angle + self.fov_angle / 2.0;
             let cell = angle / self.fov_angle;
             let cell = cell * (self.cells as f32);
-            let cell = (cell as usize).min(cells.len() - 1);
 
             let energy = (self.fov_range - dist) / self.fov_range;
-            cells[cell] += energy;
+
+            if self.smoothing > 0.0 {
+                for (idx, weight) in self.kernel(cell).into_iter().enumerate() {
+                    self.see(&mut cells[idx], energy * weight);
+                }
+            } else {
+                let cell = (cell as usize).min(cells.len() - 1);
+
+                self.see(&mut cells[cell], energy);
+            }
         }
         cells
     }
+
+    fn see(&self, cell: &mut f32, energy: f32) {
+        if self.occlusion {
+            *cell = f32::max(*cell, energy);
+        } else {
+            *cell += energy;
+        }
+    }
+
+    /// Returns how much of a food seen at (fractional) `cell` each of the
+    /// cells gets; weights add up to one
+    fn kernel(&self, cell: f32) -> Vec<f32> {
+        let weights: Vec<_> = (0..self.cells)
+            .map(|idx| {
+                let offset = (idx as f32 + 0.5 - cell) / self.smoothing;
+
+                (-0.5 * offset * offset).exp()
+            })
+            .collect();
+
+        let total: f32 = weights.iter().sum();
+
+        weights.into_iter().map(|weight| weight / total).collect()
+    }
 }
 
 impl Default for Eye {
@@ -118,7 +199,7 @@ mod tests {
     }
 
     fn food(x: f32, y: f32) -> Food {
-        Food { position: na::Point2::new(x, y) }
+        Food { position: na::Point2::new(x, y), value: 1.0 }
     }
 
     mod different_fov_ranges {
@@ -249,4 +330,107 @@ mod tests {
             }.run()
         }
     }
-}
\ No newline at end of file
+
+    mod occlusion {
+        use super::*;
+        use approx::assert_relative_eq;
+
+        fn vision(occlusion: bool) -> Vec<f32> {
+            Eye::new(1.0, FRAC_PI_2, TEST_EYE_CELLS)
+                .with_occlusion(occlusion)
+                .process_vision(
+                    na::Point2::new(0.5, 0.5),
+                    na::Rotation2::new(0.0),
+                    &[food(0.7, 0.5), food(0.9, 0.5)]
+                )
+        }
+
+        #[test]
+        fn disabled() {
+            assert_relative_eq!(vision(false)[6], 0.8 + 0.6, epsilon = 1e-6);
+        }
+
+        #[test]
+        fn enabled() {
+            assert_relative_eq!(vision(true)[6], 0.8, epsilon = 1e-6);
+        }
+    }
+
+    mod wrap {
+        use super::*;
+
+        fn vision(wrap: bool) -> Vec<f32> {
+            // Animal hugging the left edge, facing left, with food just
+            // across the seam in front of it
+            Eye::new(0.25, FRAC_PI_2, TEST_EYE_CELLS)
+                .with_wrap(wrap)
+                .process_vision(
+                    na::Point2::new(0.02, 0.5),
+                    na::Rotation2::new(PI),
+                    &[food(0.95, 0.5)]
+                )
+        }
+
+        #[test]
+        fn disabled() {
+            assert!(vision(false).iter().all(|&cell| cell == 0.0));
+        }
+
+        #[test]
+        fn enabled() {
+            let vision = vision(true);
+
+            approx::assert_relative_eq!(vision[6], (0.25 - 0.07) / 0.25, epsilon = 1e-4);
+
+            for (cell, energy) in vision.iter().enumerate() {
+                if cell != 6 {
+                    assert_eq!(*energy, 0.0);
+                }
+            }
+        }
+    }
+
+    mod smoothing {
+        use super::*;
+
+        fn vision(smoothing: f32) -> Vec<f32> {
+            // With an even number of cells, food straight ahead lies right
+            // on the boundary between the two middle ones
+            Eye::new(1.0, FRAC_PI_2, 4)
+                .with_smoothing(smoothing)
+                .process_vision(
+                    na::Point2::new(0.5, 0.5),
+                    na::Rotation2::new(0.0),
+                    &[food(0.75, 0.5)]
+                )
+        }
+
+        #[test]
+        fn disabled() {
+            approx::assert_relative_eq!(vision(0.0).as_slice(), [0.0, 0.0, 0.75, 0.0].as_slice());
+        }
+
+        #[test]
+        fn enabled() {
+            let vision = vision(0.5);
+
+            approx::assert_relative_eq!(vision[1], vision[2], epsilon = 1e-4);
+            approx::assert_relative_eq!(vision[0], vision[3], epsilon = 1e-4);
+            assert!(vision[1] > vision[0]);
+            approx::assert_relative_eq!(vision.iter().sum::<f32>(), 0.75, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn with_angular_resolution() {
+        let eye = Eye::with_angular_resolution(FRAC_PI_2, 0.25, 10.0);
+
+        assert_eq!(eye.cells(), 9);
+
+        approx::assert_relative_eq!(
+            eye.fov_angle.to_degrees() / eye.cells() as f32,
+            10.0,
+            epsilon = 1e-4
+        );
+    }
+}
@@ -3,16 +3,38 @@ use crate::*;
 #[derive(Debug)]
 pub struct Food {
     crate position: na::Point2<f32>,
+
+    /// How much satiation eating this food gives
+    crate value: f32,
 }
 
 impl Food {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        Self {
-            position: rng.gen(),
-        }
+    pub fn random(config: &SimulationConfig, rng: &mut dyn RngCore) -> Self {
+        let position = rng.gen();
+
+        Self::at(config, position, rng)
+    }
+
+    /// Creates a food at given position, drawing just its value
+    crate fn at(
+        config: &SimulationConfig,
+        position: na::Point2<f32>,
+        rng: &mut dyn RngCore
+    ) -> Self {
+        let value = if config.food_value_min < config.food_value_max {
+            rng.gen_range(config.food_value_min..config.food_value_max)
+        } else {
+            config.food_value_min
+        };
+
+        Self { position, value }
     }
 
     pub fn position(&self) -> na::Point2<f32> {
         self.position
     }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
 }
\ No newline at end of file
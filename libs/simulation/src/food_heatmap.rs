@@ -0,0 +1,73 @@
+use crate::*;
+
+/// Accumulates food positions into a grid - feed it `World::foods()` after
+/// each step to see how food is spread over the course of a run.
+#[derive(Clone, Debug)]
+pub struct FoodHeatmap {
+    rows: usize,
+    cols: usize,
+    counts: Vec<usize>
+}
+
+impl FoodHeatmap {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        assert!(rows > 0);
+        assert!(cols > 0);
+
+        Self { rows, cols, counts: vec![0; rows * cols] }
+    }
+
+    pub fn record(&mut self, foods: &[Food]) {
+        for food in foods {
+            let row = ((food.position.y * self.rows as f32) as usize).min(self.rows - 1);
+            let col = ((food.position.x * self.cols as f32) as usize).min(self.cols - 1);
+
+            self.counts[row * self.cols + col] += 1;
+        }
+    }
+
+    pub fn count(&self, row: usize, col: usize) -> usize {
+        self.counts[row * self.cols + col]
+    }
+
+    /// Returns share of all the recorded foods that fell into each cell,
+    /// row by row
+    pub fn densities(&self) -> Vec<f32> {
+        let total: usize = self.counts.iter().sum();
+
+        self.counts
+            .iter()
+            .map(|&count| {
+                if total == 0 {
+                    0.0
+                } else {
+                    count as f32 / total as f32
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn food(x: f32, y: f32) -> Food {
+        Food { position: na::Point2::new(x, y), value: 1.0 }
+    }
+
+    #[test]
+    fn test() {
+        let mut heatmap = FoodHeatmap::new(2, 2);
+
+        heatmap.record(&[food(0.1, 0.1), food(0.9, 0.1), food(0.6, 0.2)]);
+        heatmap.record(&[food(0.2, 0.7), food(1.0, 1.0)]);
+
+        assert_eq!(heatmap.count(0, 0), 1);
+        assert_eq!(heatmap.count(0, 1), 2);
+        assert_eq!(heatmap.count(1, 0), 1);
+        assert_eq!(heatmap.count(1, 1), 1);
+
+        assert_eq!(heatmap.densities(), vec![0.2, 0.4, 0.2, 0.2]);
+    }
+}
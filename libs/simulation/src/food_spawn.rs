@@ -0,0 +1,154 @@
+use crate::*;
+
+/// Describes where foods get placed
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FoodSpawn {
+    /// Anywhere in the world, with equal probability
+    #[default]
+    Uniform,
+
+    /// Around `clusters` centers picked at the beginning of each
+    /// generation, scattered with Gaussian spread of standard deviation
+    /// `spread`
+    Clustered { clusters: usize, spread: f32 },
+
+    /// In the middles of cells of a square grid covering the world, large
+    /// enough to fit `SimulationConfig::world_foods`; eaten foods respawn in
+    /// a random cell
+    Grid
+}
+
+impl FoodSpawn {
+    /// Picks cluster centers for a new generation; returns nothing (and
+    /// doesn't touch `rng`) for spawns other than `Clustered`
+    crate fn centers(&self, rng: &mut dyn RngCore) -> Vec<na::Point2<f32>> {
+        match self {
+            Self::Clustered { clusters, .. } => {
+                assert!(*clusters > 0, "Clustered spawn requires at least one cluster");
+
+                (0..*clusters).map(|_| rng.gen()).collect()
+            }
+
+            Self::Uniform | Self::Grid => Vec::new()
+        }
+    }
+
+    /// Returns position of the `idx`-th food of a new generation
+    crate fn initial_position(
+        &self,
+        config: &SimulationConfig,
+        centers: &[na::Point2<f32>],
+        idx: usize,
+        rng: &mut dyn RngCore
+    ) -> na::Point2<f32> {
+        match self {
+            Self::Grid => Self::grid_cell(config, idx),
+            Self::Uniform | Self::Clustered { .. } => self.position(config, centers, rng)
+        }
+    }
+
+    /// Returns position of a food respawned after getting eaten
+    crate fn position(
+        &self,
+        config: &SimulationConfig,
+        centers: &[na::Point2<f32>],
+        rng: &mut dyn RngCore
+    ) -> na::Point2<f32> {
+        match self {
+            Self::Uniform => rng.gen(),
+
+            Self::Clustered { spread, .. } => {
+                let center = centers[rng.gen_range(0..centers.len())];
+                let (dx, dy) = gaussian(rng);
+
+                na::Point2::new(
+                    na::wrap(center.x + dx * spread, 0.0, 1.0),
+                    na::wrap(center.y + dy * spread, 0.0, 1.0)
+                )
+            }
+
+            Self::Grid => Self::grid_cell(config, rng.gen_range(0..Self::grid_size(config).pow(2)))
+        }
+    }
+
+    fn grid_size(config: &SimulationConfig) -> usize {
+        ((config.world_foods as f32).sqrt().ceil() as usize).max(1)
+    }
+
+    fn grid_cell(config: &SimulationConfig, idx: usize) -> na::Point2<f32> {
+        let size = Self::grid_size(config);
+        let (row, col) = (idx / size % size, idx % size);
+
+        na::Point2::new(
+            (col as f32 + 0.5) / size as f32,
+            (row as f32 + 0.5) / size as f32
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+
+    fn average_pairwise_distance(spawn: FoodSpawn) -> f32 {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut food_rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            food_spawn: spawn,
+            ..Default::default()
+        };
+
+        let world = World::random(&config, &mut rng, &mut food_rng);
+        let foods = world.foods();
+
+        let mut total = 0.0;
+        let mut pairs = 0;
+
+        for (idx, a) in foods.iter().enumerate() {
+            for b in &foods[idx + 1..] {
+                total += na::distance(&a.position, &b.position);
+                pairs += 1;
+            }
+        }
+
+        total / pairs as f32
+    }
+
+    #[test]
+    fn clustered_is_denser_than_uniform() {
+        let uniform = average_pairwise_distance(FoodSpawn::Uniform);
+
+        let clustered = average_pairwise_distance(FoodSpawn::Clustered {
+            clusters: 2,
+            spread: 0.02
+        });
+
+        assert!(clustered < uniform, "{} >= {}", clustered, uniform);
+    }
+
+    #[test]
+    fn grid() {
+        let config = SimulationConfig {
+            world_foods: 4,
+            ..Default::default()
+        };
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let positions: Vec<_> = (0..4)
+            .map(|idx| FoodSpawn::Grid.initial_position(&config, &[], idx, &mut rng))
+            .collect();
+
+        assert_eq!(
+            positions,
+            vec![
+                na::Point2::new(0.25, 0.25),
+                na::Point2::new(0.75, 0.25),
+                na::Point2::new(0.25, 0.75),
+                na::Point2::new(0.75, 0.75),
+            ]
+        );
+    }
+}
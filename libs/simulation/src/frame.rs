@@ -0,0 +1,113 @@
+use crate::*;
+use std::f32::consts::PI;
+
+/// Snapshot of world's state, as needed for rendering a replay
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub animals: Vec<AnimalFrame>,
+    pub foods: Vec<na::Point2<f32>>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimalFrame {
+    pub position: na::Point2<f32>,
+    pub rotation: na::Rotation2<f32>
+}
+
+impl Frame {
+    /// Interpolates between this frame (`t = 0.0`) and `other` (`t = 1.0`),
+    /// turning each animal through the shorter of the two possible angles.
+    ///
+    /// Foods don't move, so they are taken from whichever frame is closer.
+    pub fn lerp(&self, other: &Frame, t: f32) -> Frame {
+        assert_eq!(
+            self.animals.len(),
+            other.animals.len(),
+            "Cannot interpolate between frames with different number of animals"
+        );
+
+        let animals = self
+            .animals
+            .iter()
+            .zip(&other.animals)
+            .map(|(from, to)| {
+                let position = from.position + (to.position - from.position) * t;
+
+                let delta = na::wrap(to.rotation.angle() - from.rotation.angle(), -PI, PI);
+                let rotation = na::Rotation2::new(from.rotation.angle() + delta * t);
+
+                AnimalFrame { position, rotation }
+            })
+            .collect();
+
+        let foods = if t < 0.5 { &self.foods } else { &other.foods };
+
+        Frame {
+            animals,
+            foods: foods.clone()
+        }
+    }
+}
+
+impl From<&World> for Frame {
+    fn from(world: &World) -> Self {
+        let animals = world
+            .animals
+            .iter()
+            .map(|animal| AnimalFrame {
+                position: animal.position,
+                rotation: animal.rotation
+            })
+            .collect();
+
+        let foods = world.foods.iter().map(|food| food.position).collect();
+
+        Self { animals, foods }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn frame(animals: &[(f32, f32, f32)]) -> Frame {
+        Frame {
+            animals: animals
+                .iter()
+                .map(|&(x, y, angle)| AnimalFrame {
+                    position: na::Point2::new(x, y),
+                    rotation: na::Rotation2::new(angle)
+                })
+                .collect(),
+            foods: vec![]
+        }
+    }
+
+    #[test]
+    fn lerp() {
+        let a = frame(&[(0.1, 0.2, 0.5), (0.0, 0.0, TAU - 0.2)]);
+        let b = frame(&[(0.3, 0.6, 1.5), (1.0, 0.5, 0.4)]);
+
+        let frame = a.lerp(&b, 0.5);
+
+        assert_relative_eq!(frame.animals[0].position, na::Point2::new(0.2, 0.4));
+        assert_relative_eq!(frame.animals[0].rotation.angle(), 1.0);
+
+        assert_relative_eq!(frame.animals[1].position, na::Point2::new(0.5, 0.25));
+
+        // Turning from -0.2 to 0.4 through the seam, instead of the long
+        // way around
+        assert_relative_eq!(frame.animals[1].rotation.angle(), 0.1, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn lerp_endpoints() {
+        let a = frame(&[(0.1, 0.2, 0.5)]);
+        let b = frame(&[(0.3, 0.6, -2.5)]);
+
+        assert_relative_eq!(a.lerp(&b, 0.0).animals[0].position, a.animals[0].position);
+        assert_relative_eq!(a.lerp(&b, 1.0).animals[0].position, b.animals[0].position);
+        assert_relative_eq!(a.lerp(&b, 1.0).animals[0].rotation.angle(), -2.5, epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::*;
+
+/// Decides when the current generation is over and the animals evolve
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GenerationLength {
+    /// Generation lasts given number of steps
+    Fixed(usize),
+
+    /// Generation lasts until all the food is eaten; eaten food doesn't
+    /// respawn in this mode (so if animals never manage to eat everything,
+    /// the generation never ends)
+    UntilAllEaten,
+
+    /// Generation lasts `base` steps plus `per_food` steps for each food in
+    /// the world
+    Adaptive { base: usize, per_food: usize }
+}
+
+impl GenerationLength {
+    crate fn is_over(&self, age: usize, world: &World) -> bool {
+        match *self {
+            Self::Fixed(length) => age > length,
+            Self::UntilAllEaten => world.foods.is_empty(),
+            Self::Adaptive { base, per_food } => age > base + per_food * world.foods.len()
+        }
+    }
+}
+
+impl Default for GenerationLength {
+    fn default() -> Self {
+        Self::Fixed(2500)
+    }
+}
@@ -0,0 +1,12 @@
+use crate::*;
+
+/// Summary of a generation that has just finished, as returned by
+/// `Simulation::step()` and `Simulation::train()`
+#[derive(Clone, Debug)]
+pub struct GenerationSummary {
+    /// Fitness statistics of the population before it got evolved
+    pub statistics: ga::Statistics,
+
+    /// Average length of path the animals traveled during the generation
+    pub average_distance_traveled: f32
+}
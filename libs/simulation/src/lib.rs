@@ -1,68 +1,311 @@
 #![feature(crate_visibility_modifier)]
-pub use self::{animal::*, eye::*, food::*, world::*};
+pub use self::{
+    action_selection::*, animal::*, boundary::*, config::*, eye::*, food::*, food_heatmap::*,
+    food_spawn::*, frame::*, generation_length::*, generation_summary::*, locomotion::*, world::*
+};
 
+#[cfg(feature = "testing")]
+pub use self::testing::*;
+
+mod action_selection;
 mod animal;
 mod animal_individual;
+mod boundary;
+mod bounding_circle;
+mod config;
 mod eye;
 mod food;
+mod food_heatmap;
+mod food_spawn;
+mod frame;
+mod generation_length;
+mod generation_summary;
+mod locomotion;
+#[cfg(feature = "testing")]
+mod testing;
 mod world;
 
 use self::animal_individual::*;
+use self::bounding_circle::*;
 use lib_genetic_algorithm as ga;
 use lib_neural_network as nn;
 use nalgebra as na;
 use rand::prelude::*;
-use std::f32::consts::FRAC_PI_2;
+use rand_chacha::ChaCha8Rng;
+use std::f32::consts::{FRAC_PI_2, TAU};
 
-const SPEED_MIN: f32 = 0.001;
-const SPEED_MAX: f32 = 0.005;
-const SPEED_ACCEL: f32 = 0.2;
 const ROTATION_ACCEL: f32 = FRAC_PI_2;
-const GENERATION_LENGTH: usize = 2500;
+const FOOD_RADIUS: f32 = 0.01;
+
+/// Returns a pair of independent samples from the standard normal
+/// distribution (using the Box-Muller transform)
+fn gaussian(rng: &mut dyn RngCore) -> (f32, f32) {
+    let u1: f32 = 1.0 - rng.gen::<f32>();
+    let u2: f32 = rng.gen();
+
+    let radius = (-2.0 * u1.ln()).sqrt();
+    let angle = TAU * u2;
+
+    (radius * angle.cos(), radius * angle.sin())
+}
 
 pub struct Simulation {
+    config: SimulationConfig,
     world: World,
-    ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
-    age: usize
+    ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection, ga::UniformCrossover, ga::GaussianMutation>,
+    age: usize,
+
+    /// Separate stream used for placing foods, so that brains and foods
+    /// can be reproduced independently of each other
+    food_rng: ChaCha8Rng
 }
 
 impl Simulation {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let world: World::random(rng),
-        
+    pub fn random(config: SimulationConfig, rng: &mut dyn RngCore) -> Self {
+        let food_seed = rng.gen();
+
+        Self::random_with_food_seed(config, rng, food_seed)
+    }
+
+    /// Like `random()`, but with foods placed using a stream seeded
+    /// separately - `rng` is then used only for animals and evolution.
+    pub fn random_with_food_seed(
+        config: SimulationConfig,
+        rng: &mut dyn RngCore,
+        food_seed: u64
+    ) -> Self {
+        assert!(
+            config.brain_outputs >= config.locomotion.required_outputs(),
+            "{:?} requires at least {} brain outputs",
+            config.locomotion,
+            config.locomotion.required_outputs()
+        );
+
+        let mut food_rng = ChaCha8Rng::seed_from_u64(food_seed);
+        let world = World::random(&config, rng, &mut food_rng);
+
         let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection::default(),
-            ga::UniformCrossover::default(),
+            ga::RouletteWheelSelection::new(),
+            ga::UniformCrossover::new(),
             ga::GaussianMutation::new(0.01, 0.3)
         );
-        
-        Self { world, ga, age: 0 }
+
+        Self {
+            config,
+            world,
+            ga,
+            age: 0,
+            food_rng
+        }
+    }
+
+    /// Starts over with a world rebuilt from given seed, keeping the
+    /// configuration
+    pub fn reseed(&mut self, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        *self = Self::random(self.config.clone(), &mut rng);
     }
-}
 
     pub fn world(&self) -> &World {
         &self.world
     }
 
-    pub fn step(&mut self, rng: &mut dyn RngCore) {
-        self.process_collisions(rng);
-        self.process_brains();
+    /// Returns what the `index`-th animal currently sees, i.e. what its
+    /// brain will get as inputs in the next step
+    pub fn animal_vision(&self, index: usize) -> Vec<f32> {
+        let animal = &self.world.animals[index];
+
+        animal.eye.process_vision(animal.position, animal.rotation, &self.world.foods)
+    }
+
+    /// Returns the average length of path the animals have traveled so far
+    /// in the current generation
+    pub fn average_distance_traveled(&self) -> f32 {
+        if self.world.animals.is_empty() {
+            return 0.0;
+        }
+
+        let total: f32 = self
+            .world
+            .animals
+            .iter()
+            .map(|animal| animal.distance_traveled)
+            .sum();
+
+        total / self.world.animals.len() as f32
+    }
+
+    /// Performs a single step; when it finishes the current generation,
+    /// returns summary of the generation that just got evolved.
+    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<GenerationSummary> {
+        self.process_collisions();
+        self.process_brains(rng, None, None);
         self.process_movements();
+        self.finish_step(rng)
+    }
+
+    /// Performs `steps` steps, returning what the `index`-th animal saw
+    /// (including `vision_noise`) and how its brain responded in each of
+    /// them.
+    ///
+    /// Generations can end in the meantime, in which case the trace
+    /// continues with whichever animal takes the `index`-th place.
+    pub fn trace_animal(
+        &mut self,
+        index: usize,
+        steps: usize,
+        rng: &mut dyn RngCore
+    ) -> Vec<(Vec<f32>, Vec<f32>)> {
+        (0..steps)
+            .map(|_| {
+                self.process_collisions();
+
+                let trace = self
+                    .process_brains(rng, Some(index), None)
+                    .expect("there's no animal with such index");
+
+                self.process_movements();
+                self.finish_step(rng);
+
+                trace
+            })
+            .collect()
+    }
+
+    /// Performs `steps` steps, counting how many times each action (of
+    /// `Locomotion::Discrete`, as picked by the configured
+    /// `ActionSelection`) got chosen across all the animals.
+    ///
+    /// Panics for locomotions other than `Locomotion::Discrete`.
+    pub fn action_histogram(&mut self, rng: &mut dyn RngCore, steps: usize) -> Vec<usize> {
+        assert!(
+            self.config.locomotion == Locomotion::Discrete,
+            "Action histogram requires discrete locomotion"
+        );
+
+        let mut histogram = vec![0; Locomotion::Discrete.required_outputs()];
 
+        for _ in 0..steps {
+            self.process_collisions();
+            self.process_brains(rng, None, Some(&mut histogram));
+            self.process_movements();
+            self.finish_step(rng);
+        }
+
+        histogram
+    }
+
+    fn finish_step(&mut self, rng: &mut dyn RngCore) -> Option<GenerationSummary> {
         self.age += 1;
 
-        if self.age > GENERATION_LENGTH {
-            self.evolve(rng);
+        if self.config.generation_length.is_over(self.age, &self.world) {
+            Some(self.evolve(rng))
+        } else {
+            None
         }
-        
     }
 
-    fn process_collisions(&mut self, rng: &mut dyn RngCore) {
+    /// Fast-forwards until the end of the current generation
+    pub fn train(&mut self, rng: &mut dyn RngCore) -> GenerationSummary {
+        loop {
+            if let Some(summary) = self.step(rng) {
+                return summary;
+            }
+        }
+    }
+
+    /// Returns how much animals herd together - the average number of
+    /// other animals within `radius` of each animal, as a fraction of all
+    /// the other animals (so 0.0 = nobody has any neighbours, 1.0 = everyone
+    /// is within `radius` of everyone else)
+    pub fn clustering(&self, radius: f32) -> f32 {
+        let animals = &self.world.animals;
+
+        if animals.len() < 2 {
+            return 0.0;
+        }
+
+        let mut neighbours = 0;
+
+        for (idx, a) in animals.iter().enumerate() {
+            for b in &animals[idx + 1..] {
+                if na::distance(&a.position, &b.position) <= radius {
+                    neighbours += 2;
+                }
+            }
+        }
+
+        neighbours as f32 / (animals.len() * (animals.len() - 1)) as f32
+    }
+
+    /// Returns how contested foods are - the average number of animals
+    /// within `radius` of each food (so 0.0 = no food has any animal
+    /// nearby)
+    pub fn food_competition(&self, radius: f32) -> f32 {
+        let foods = &self.world.foods;
+
+        if foods.is_empty() {
+            return 0.0;
+        }
+
+        let competitors: usize = foods
+            .iter()
+            .map(|food| {
+                self.world
+                    .animals
+                    .iter()
+                    .filter(|animal| na::distance(&food.position, &animal.position) <= radius)
+                    .count()
+            })
+            .sum();
+
+        competitors as f32 / foods.len() as f32
+    }
+
+    /// Returns center and radius of the smallest circle containing all
+    /// the animals (e.g. for auto-framing the camera)
+    pub fn bounding_circle(&self) -> (na::Point2<f32>, f32) {
+        let positions: Vec<_> = self.world.animals.iter().map(|animal| animal.position).collect();
+
+        bounding_circle(&positions)
+    }
+
+    fn process_collisions(&mut self) {
+        if self.config.generation_length == GenerationLength::UntilAllEaten {
+            let animals = &mut self.world.animals;
+
+            self.world.foods.retain(|food| {
+                let eater = animals.iter_mut().find(|animal| {
+                    na::distance(&animal.position, &food.position) <= FOOD_RADIUS
+                });
+
+                match eater {
+                    Some(animal) => {
+                        animal.satiation += food.value;
+                        false
+                    }
+                    None => true
+                }
+            });
+
+            return;
+        }
+
         for animal in &mut self.world.animals {
             for food in &mut self.world.foods {
                 let distance = na::distance(&animal.position, &food.position);
-                if distance <= 0.01 {
-                    food.position = rng.gen();
+
+                if distance <= FOOD_RADIUS {
+                    animal.satiation += food.value;
+
+                    let position = self.config.food_spawn.position(
+                        &self.config,
+                        &self.world.food_centers,
+                        &mut self.food_rng
+                    );
+
+                    *food = Food::at(&self.config, position, &mut self.food_rng);
                 }
             }
         }
@@ -70,49 +313,644 @@ impl Simulation {
 
     fn process_movements(&mut self) {
         for animal in &mut self.world.animals {
-            animal.position += animal.rotation * na::Vector2::new(animal.speed, 0.0);
+            let distance = animal.speed * self.config.dt;
 
-            animal.position.x = na::wrap(animal.position.x, 0.0, 1.0);
-            animal.position.y = na::wrap(animal.position.y, 0.0, 1.0);
+            let position = animal.position + animal.rotation * na::Vector2::new(distance, 0.0);
+            let (position, rotation) = self.config.boundary.apply(position, animal.rotation);
+
+            // Speed is negative when driving backwards; as for the boundary,
+            // wrapping and reflecting keep the path's length, while clamping
+            // cuts it short
+            animal.distance_traveled += match self.config.boundary {
+                Boundary::Wrap | Boundary::Reflect => distance.abs(),
+                Boundary::Clamp => na::distance(&animal.position, &position)
+            };
+
+            animal.position = position;
+            animal.rotation = rotation;
         }
     }
 
-    fn process_brains(&mut self) {
-        for animal in &mut self.world.animals {
-            let vision = animal.eye.process_vision(
-                animal.position, 
-                animal.rotation, 
+    /// Feeds each animal's vision into its brain and applies the response;
+    /// returns both of them for the `traced`-th animal, and tallies chosen
+    /// actions into `actions`
+    fn process_brains(
+        &mut self,
+        rng: &mut dyn RngCore,
+        traced: Option<usize>,
+        mut actions: Option<&mut [usize]>
+    ) -> Option<(Vec<f32>, Vec<f32>)> {
+        let mut trace = None;
+
+        for (idx, animal) in self.world.animals.iter_mut().enumerate() {
+            let mut vision = animal.eye.process_vision(
+                animal.position,
+                animal.rotation,
                 &self.world.foods
             );
 
-            let response = animal.brain.propagate(vision);
+            if self.config.vision_noise > 0.0 {
+                for cell in &mut vision {
+                    *cell += gaussian(rng).0 * self.config.vision_noise;
+                }
+            }
 
-            let speed = response[0].clamp(
-                -SPEED_ACCEL, 
-                SPEED_ACCEL
-            );
+            let response = if traced == Some(idx) {
+                let response = animal.brain.propagate(vision.clone());
+                trace = Some((vision, response.clone()));
+                response
+            } else {
+                animal.brain.propagate(vision)
+            };
 
-            let rotation = response[1].clamp(
-                -ROTATION_ACCEL, 
-                ROTATION_ACCEL
+            let (speed, rotation, action) = self.config.locomotion.apply(
+                &self.config,
+                &response,
+                animal.speed,
+                animal.rotation,
+                rng
             );
 
-            animal.speed = (animal.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
-            animal.rotation = na::Rotation2::new(animal.rotation.angle() + rotation);
+            if let (Some(actions), Some(action)) = (actions.as_deref_mut(), action) {
+                actions[action] += 1;
+            }
+
+            animal.speed = speed;
+            animal.rotation = rotation;
         }
+
+        trace
     }
 
-    fn evolve(&mut self, rng: &mut dyn RngCore) {
+    fn evolve(&mut self, rng: &mut dyn RngCore) -> GenerationSummary {
         self.age = 0;
 
-        let current_population = ;
+        // Has to be computed before the animals get replaced, since the
+        // evolved ones start with a clean slate
+        let average_distance_traveled = self.average_distance_traveled();
 
+        let current_population: Vec<_> = self
+            .world
+            .animals
+            .iter()
+            .map(AnimalIndividual::from_animal)
+            .collect();
+
+        let statistics = ga::Statistics::new(&current_population);
         let evolved_population = self.ga.evolve(rng, &current_population);
 
-        self.world.animals = ;
+        self.world.animals = evolved_population
+            .into_iter()
+            .map(|individual| individual.into_animal(&self.config, rng))
+            .collect();
+
+        if self.config.food_replenish_per_generation > 0 {
+            self.world.replenish_foods(
+                &self.config,
+                &mut self.food_rng,
+                self.config.food_replenish_per_generation,
+                self.config.food_max
+            );
+        } else {
+            self.world.respawn_foods(&self.config, &mut self.food_rng);
+        }
+
+        GenerationSummary { statistics, average_distance_traveled }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn until_all_eaten() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            generation_length: GenerationLength::UntilAllEaten,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        sim.world.animals.truncate(1);
+        sim.world.animals[0].position = na::Point2::new(0.5, 0.5);
+
+        sim.world.foods = vec![
+            Food { position: na::Point2::new(0.5, 0.5), value: 1.0 },
+            Food { position: na::Point2::new(0.1, 0.1), value: 1.0 },
+        ];
+
+        assert!(sim.step(&mut rng).is_none());
+        assert_eq!(sim.world.foods.len(), 1);
+
+        sim.world.animals[0].position = na::Point2::new(0.1, 0.1);
+
+        let summary = sim.step(&mut rng).expect("generation should be over");
+
+        assert_eq!(summary.statistics.max_fitness(), 2.0);
+        assert_eq!(sim.age, 0);
+        assert_eq!(sim.world.foods.len(), sim.config.world_foods);
+    }
+
+    #[test]
+    fn dt() {
+        let position_after = |dt, steps| {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let config = SimulationConfig {
+                world_animals: 1,
+                dt,
+                ..Default::default()
+            };
+
+            let mut sim = Simulation::random(config, &mut rng);
+
+            sim.world.animals[0].position = na::Point2::new(0.5, 0.5);
+
+            for _ in 0..steps {
+                sim.process_movements();
+            }
+
+            sim.world.animals[0].position
+        };
+
+        let actual = position_after(0.5, 2);
+        let expected = position_after(1.0, 1);
+
+        approx::assert_relative_eq!(actual, expected);
+    }
+
+    #[test]
+    fn average_distance_traveled() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 1,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        sim.world.animals[0].speed = 0.003;
+
+        for _ in 0..10 {
+            sim.process_movements();
+        }
+
+        approx::assert_relative_eq!(sim.average_distance_traveled(), 0.03);
+
+        sim.world.animals[0].satiation = 1.0;
+        sim.evolve(&mut rng);
+
+        assert_eq!(sim.average_distance_traveled(), 0.0);
+    }
+
+    #[test]
+    fn distance_traveled_backwards_and_against_walls() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 1,
+            boundary: Boundary::Clamp,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        let animal = &mut sim.world.animals[0];
+        animal.position = na::Point2::new(0.5, 0.5);
+        animal.rotation = na::Rotation2::new(0.0);
+        animal.speed = -0.003;
+
+        for _ in 0..10 {
+            sim.process_movements();
+        }
+
+        approx::assert_relative_eq!(sim.average_distance_traveled(), 0.03, epsilon = 1e-6);
+
+        // Pinned against the left wall, so it can't get any further
+        sim.world.animals[0].position = na::Point2::new(0.001, 0.5);
+
+        for _ in 0..10 {
+            sim.process_movements();
+        }
+
+        approx::assert_relative_eq!(sim.average_distance_traveled(), 0.031, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn average_distance_traveled_in_summary() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        for food in &mut self.world.foods {
-            food.position = rng.gen();
+        let config = SimulationConfig {
+            world_animals: 1,
+            generation_length: GenerationLength::Fixed(9),
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        let animal = &mut sim.world.animals[0];
+        let topology = Animal::topology(&sim.config, &animal.eye);
+        let weights = animal.brain.weights().count();
+
+        // Brain that always responds with zeros, i.e. keeps going straight at
+        // the current speed
+        animal.brain = nn::Network::from_weights(&topology, vec![0.0; weights]);
+        animal.speed = 0.003;
+        animal.satiation = 1.0;
+
+        for _ in 0..9 {
+            assert!(sim.step(&mut rng).is_none());
+        }
+
+        let summary = sim.step(&mut rng).expect("generation should be over");
+
+        approx::assert_relative_eq!(summary.average_distance_traveled, 0.03, epsilon = 1e-6);
+        assert_eq!(sim.average_distance_traveled(), 0.0);
+    }
+
+    #[test]
+    fn reseed() {
+        let mut a = Simulation::random(Default::default(), &mut ChaCha8Rng::seed_from_u64(1));
+        let mut b = Simulation::random(Default::default(), &mut ChaCha8Rng::seed_from_u64(2));
+
+        for _ in 0..10 {
+            b.step(&mut ChaCha8Rng::seed_from_u64(3));
+        }
+
+        a.reseed(42);
+        b.reseed(42);
+
+        assert_eq!(b.age, 0);
+
+        for (a, b) in a.world.animals.iter().zip(&b.world.animals) {
+            assert_eq!(a.position, b.position);
+            assert!(a.as_chromosome().iter().eq(b.as_chromosome().iter()));
+        }
+
+        for (a, b) in a.world.foods.iter().zip(&b.world.foods) {
+            assert_eq!(a.position, b.position);
+        }
+    }
+
+    #[test]
+    fn clustering() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 4,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        let mut place = |positions: [(f32, f32); 4]| {
+            for (animal, (x, y)) in sim.world.animals.iter_mut().zip(positions) {
+                animal.position = na::Point2::new(x, y);
+            }
+
+            sim.clustering(0.05)
+        };
+
+        let clustered = place([(0.50, 0.50), (0.51, 0.50), (0.50, 0.51), (0.52, 0.52)]);
+        let spread = place([(0.10, 0.10), (0.90, 0.10), (0.10, 0.90), (0.12, 0.12)]);
+
+        approx::assert_relative_eq!(clustered, 1.0);
+        approx::assert_relative_eq!(spread, 1.0 / 6.0);
+    }
+
+    #[test]
+    fn bounding_circle() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 5,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        let positions = [(0.2, 0.2), (0.8, 0.2), (0.5, 0.5), (0.2, 0.8), (0.8, 0.8)];
+
+        for (animal, (x, y)) in sim.world.animals.iter_mut().zip(positions) {
+            animal.position = na::Point2::new(x, y);
+        }
+
+        let (center, radius) = sim.bounding_circle();
+
+        approx::assert_relative_eq!(center, na::Point2::new(0.5, 0.5), epsilon = 1e-6);
+        approx::assert_relative_eq!(radius, 0.3 * 2.0f32.sqrt(), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn food_competition() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 8,
+            world_foods: 2,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        sim.world.foods[0].position = na::Point2::new(0.2, 0.2);
+        sim.world.foods[1].position = na::Point2::new(0.8, 0.8);
+
+        let mut place = |positions: &dyn Fn(usize) -> (f32, f32)| {
+            for (idx, animal) in sim.world.animals.iter_mut().enumerate() {
+                let (x, y) = positions(idx);
+                animal.position = na::Point2::new(x, y);
+            }
+
+            sim.food_competition(0.05)
+        };
+
+        // Everyone crowds around the first food
+        let contested = place(&|idx| (0.2 + 0.005 * idx as f32, 0.2));
+
+        // Animals spread along the diagonal, one of them next to each food
+        let spread = place(&|idx| (0.1 * idx as f32 + 0.1, 0.1 * idx as f32 + 0.1));
+
+        approx::assert_relative_eq!(contested, 8.0 / 2.0);
+        approx::assert_relative_eq!(spread, 2.0 / 2.0);
+    }
+
+    #[test]
+    fn animal_vision() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 2,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        sim.world.animals[1].position = na::Point2::new(0.5, 0.5);
+        sim.world.animals[1].rotation = na::Rotation2::new(0.0);
+
+        sim.world.foods = vec![
+            Food { position: na::Point2::new(0.6, 0.5), value: 1.0 },
+            Food { position: na::Point2::new(0.5, 0.6), value: 1.0 }
+        ];
+
+        let expected = Eye::default().process_vision(
+            na::Point2::new(0.5, 0.5),
+            na::Rotation2::new(0.0),
+            &sim.world.foods
+        );
+
+        let actual = sim.animal_vision(1);
+
+        assert_eq!(actual, expected);
+        assert!(actual.iter().any(|&cell| cell > 0.0));
+    }
+
+    #[test]
+    fn trace_animal() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut sim = Simulation::random(Default::default(), &mut rng);
+
+        let trace = sim.trace_animal(3, 25, &mut rng);
+
+        assert_eq!(trace.len(), 25);
+        assert_eq!(sim.age, 25);
+
+        for (vision, response) in &trace {
+            assert_eq!(vision.len(), sim.world.animals[3].eye.cells());
+            assert_eq!(response.len(), sim.config.brain_outputs);
+        }
+    }
+
+    #[test]
+    fn vision_noise() {
+        let trace = |vision_noise, seed| {
+            let config = SimulationConfig {
+                vision_noise,
+                ..Default::default()
+            };
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut sim = Simulation::random(config, &mut rng);
+
+            // Make sure there's something to see
+            let animal = &sim.world.animals[0];
+            let ahead = animal.rotation * na::Vector2::new(0.1, 0.0);
+            sim.world.foods[0].position = animal.position + ahead;
+
+            sim.trace_animal(0, 1, &mut ChaCha8Rng::seed_from_u64(seed)).remove(0).0
+        };
+
+        assert_eq!(trace(0.0, 1), trace(0.0, 2));
+
+        let (a, b) = (trace(0.05, 1), trace(0.05, 2));
+
+        assert_ne!(a, b);
+
+        for (a, b) in a.iter().zip(&b) {
+            assert!((a - b).abs() < 0.5);
+        }
+    }
+
+    #[test]
+    fn food_replenishment() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 2,
+            world_foods: 10,
+            generation_length: GenerationLength::Fixed(5),
+            food_replenish_per_generation: 3,
+            food_max: Some(17),
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+        let mut counts = vec![sim.world.foods.len()];
+
+        for _ in 0..3 {
+            // Makes sure there's some fitness to select by
+            for animal in &mut sim.world.animals {
+                animal.satiation = 1.0;
+            }
+
+            sim.train(&mut rng);
+            counts.push(sim.world.foods.len());
+        }
+
+        assert_eq!(counts, vec![10, 13, 16, 17]);
+    }
+
+    #[test]
+    fn food_replenishment_until_all_eaten() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 1,
+            world_foods: 10,
+            generation_length: GenerationLength::UntilAllEaten,
+            food_spawn: FoodSpawn::Clustered {
+                clusters: 2,
+                spread: 0.05
+            },
+            food_replenish_per_generation: 3,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+        let centers = sim.world.food_centers.clone();
+
+        sim.world.animals[0].position = na::Point2::new(0.5, 0.5);
+        sim.world.foods = vec![Food { position: na::Point2::new(0.5, 0.5), value: 1.0 }];
+
+        assert!(sim.step(&mut rng).is_some());
+        assert_eq!(sim.world.foods.len(), 3);
+        assert_eq!(sim.world.food_centers, centers);
+    }
+
+    #[test]
+    fn brain_activation_survives_evolution() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let slope = 0.2;
+
+        let config = SimulationConfig {
+            world_animals: 5,
+            generation_length: GenerationLength::Fixed(10),
+            brain_activation: nn::Activation::LeakyRelu { slope },
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        for animal in &mut sim.world.animals {
+            animal.satiation = 1.0;
+        }
+
+        sim.train(&mut rng);
+
+        let activations: Vec<_> =
+            sim.world.animals.iter().flat_map(|animal| animal.brain.activations()).collect();
+
+        let expected = [nn::Activation::LeakyRelu { slope }, nn::Activation::Relu];
+
+        assert!(activations.contains(&expected[0]));
+        assert!(activations.iter().all(|activation| expected.contains(activation)));
+    }
+
+    #[test]
+    fn food_value() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 1,
+            generation_length: GenerationLength::UntilAllEaten,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        sim.world.foods = vec![
+            Food { position: na::Point2::new(0.2, 0.2), value: 1.0 },
+            Food { position: na::Point2::new(0.6, 0.6), value: 2.0 },
+            Food { position: na::Point2::new(0.9, 0.1), value: 1.0 }
+        ];
+
+        let mut gain_at = |position| {
+            let satiation = sim.world.animals[0].satiation;
+
+            sim.world.animals[0].position = position;
+            sim.step(&mut rng);
+            sim.world.animals[0].satiation - satiation
+        };
+
+        let gain_1 = gain_at(na::Point2::new(0.2, 0.2));
+        let gain_2 = gain_at(na::Point2::new(0.6, 0.6));
+
+        assert_eq!(gain_1, 1.0);
+        assert_eq!(gain_2, 2.0 * gain_1);
+    }
+
+    #[test]
+    fn speed_min() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 1,
+            locomotion: Locomotion::DifferentialDrive,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        let animal = &mut sim.world.animals[0];
+        let topology = Animal::topology(&sim.config, &animal.eye);
+        let weights = animal.brain.weights().count();
+
+        // Brain that always responds with zeros, i.e. commands zero speed
+        animal.brain = nn::Network::from_weights(&topology, vec![0.0; weights]);
+        animal.position = na::Point2::new(0.5, 0.5);
+        animal.rotation = na::Rotation2::new(0.0);
+
+        sim.process_brains(&mut rng, None, None);
+        sim.process_movements();
+
+        let animal = &sim.world.animals[0];
+
+        assert_eq!(animal.speed, sim.config.speed_min);
+        approx::assert_relative_eq!(animal.position.x, 0.5 + sim.config.speed_min);
+    }
+
+    #[test]
+    fn action_histogram() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 5,
+            generation_length: GenerationLength::Fixed(1000),
+            locomotion: Locomotion::Discrete,
+            brain_outputs: 3,
+            ..Default::default()
+        };
+
+        let mut sim = Simulation::random(config, &mut rng);
+
+        for animal in &mut sim.world.animals {
+            let topology = Animal::topology(&sim.config, &animal.eye);
+            let mut weights = vec![0.0; animal.brain.weights().count()];
+
+            // Brain that ignores its inputs and always prefers turning left:
+            // all weights are zero, except for the second output's bias
+            let output_layer = topology[1].neurons * (topology[0].neurons + 1);
+            weights[output_layer + topology[1].neurons + 1] = 1.0;
+
+            animal.brain = nn::Network::from_weights(&topology, weights)
+                .with_activation(sim.config.brain_activation);
+        }
+
+        assert_eq!(sim.action_histogram(&mut rng, 20), vec![0, 5 * 20, 0]);
+    }
+
+    #[test]
+    fn independent_food_seed() {
+        let simulation = |food_seed| {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            Simulation::random_with_food_seed(Default::default(), &mut rng, food_seed)
+        };
+
+        let (a, b) = (simulation(1), simulation(2));
+
+        for (a, b) in a.world.animals.iter().zip(&b.world.animals) {
+            assert!(a.as_chromosome().iter().eq(b.as_chromosome().iter()));
+        }
+
+        for (a, b) in a.world.foods.iter().zip(&b.world.foods) {
+            assert_ne!(a.position, b.position);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,204 @@
+use crate::*;
+
+/// Distance between the wheels of a `Locomotion::DifferentialDrive` animal
+const AXLE_WIDTH: f32 = 0.01;
+
+/// Angle a `Locomotion::Discrete` animal turns by in a single step
+const DISCRETE_TURN: f32 = ROTATION_ACCEL / 4.0;
+
+/// Describes how brain outputs are turned into movement
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Locomotion {
+    /// Outputs are changes of speed and rotation, respectively
+    #[default]
+    SpeedRotation,
+
+    /// Outputs are speeds of the left and right wheel, respectively, each
+    /// given as a fraction of `SimulationConfig::speed_max` (negative values
+    /// drive backwards)
+    DifferentialDrive,
+
+    /// Outputs are scores of three actions - keep going straight, turn left
+    /// and turn right - out of which one gets picked according to
+    /// `SimulationConfig::action_selection`; speed stays unchanged
+    Discrete
+}
+
+impl Locomotion {
+    /// Number of brain outputs this locomotion reads
+    pub fn required_outputs(&self) -> usize {
+        match self {
+            Self::SpeedRotation | Self::DifferentialDrive => 2,
+            Self::Discrete => 3
+        }
+    }
+
+    /// Returns new speed and rotation, along with which action got picked
+    /// (for `Self::Discrete`)
+    crate fn apply(
+        &self,
+        config: &SimulationConfig,
+        response: &[f32],
+        speed: f32,
+        rotation: na::Rotation2<f32>,
+        rng: &mut dyn RngCore
+    ) -> (f32, na::Rotation2<f32>, Option<usize>) {
+        match self {
+            Self::SpeedRotation => {
+                let speed_delta = response[0].clamp(-config.speed_accel, config.speed_accel);
+                let rotation_delta = response[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+
+                (
+                    (speed + speed_delta).clamp(config.speed_min, config.speed_max),
+                    na::Rotation2::new(rotation.angle() + rotation_delta),
+                    None
+                )
+            }
+
+            Self::DifferentialDrive => {
+                let left = response[0].clamp(-1.0, 1.0) * config.speed_max;
+                let right = response[1].clamp(-1.0, 1.0) * config.speed_max;
+
+                let speed = (left + right) / 2.0;
+                let rotation_delta = (right - left) / AXLE_WIDTH;
+
+                // Clamps magnitude, so that driving backwards is preserved
+                let speed = if speed < 0.0 {
+                    -(-speed).clamp(config.speed_min, config.speed_max)
+                } else {
+                    speed.clamp(config.speed_min, config.speed_max)
+                };
+
+                (speed, na::Rotation2::new(rotation.angle() + rotation_delta), None)
+            }
+
+            Self::Discrete => {
+                let action = config.action_selection.select(&response[..3], rng);
+
+                let rotation_delta = match action {
+                    0 => 0.0,
+                    1 => DISCRETE_TURN,
+                    _ => -DISCRETE_TURN
+                };
+
+                (
+                    speed.clamp(config.speed_min, config.speed_max),
+                    na::Rotation2::new(rotation.angle() + rotation_delta),
+                    Some(action)
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn speed_rotation() {
+        let (speed, rotation, _) = Locomotion::SpeedRotation.apply(
+            &SimulationConfig::default(),
+            &[0.001, 0.5],
+            0.002,
+            na::Rotation2::new(0.0),
+            &mut ChaCha8Rng::from_seed(Default::default())
+        );
+
+        assert_relative_eq!(speed, 0.003);
+        assert_relative_eq!(rotation.angle(), 0.5);
+    }
+
+    #[test]
+    fn speed_rotation_acceleration_limit() {
+        let config = SimulationConfig {
+            speed_accel: 0.001,
+            ..Default::default()
+        };
+
+        let (speed, _, _) = Locomotion::SpeedRotation.apply(
+            &config,
+            &[100.0, 0.0],
+            0.002,
+            na::Rotation2::new(0.0),
+            &mut ChaCha8Rng::from_seed(Default::default())
+        );
+
+        assert_relative_eq!(speed, 0.003);
+
+        let (speed, _, _) = Locomotion::SpeedRotation.apply(
+            &config,
+            &[-100.0, 0.0],
+            0.0045,
+            na::Rotation2::new(0.0),
+            &mut ChaCha8Rng::from_seed(Default::default())
+        );
+
+        assert_relative_eq!(speed, 0.0035);
+    }
+
+    #[test]
+    fn differential_drive() {
+        let config = SimulationConfig::default();
+
+        let (speed, rotation, _) = Locomotion::DifferentialDrive.apply(
+            &config,
+            &[0.2, 0.6],
+            0.002,
+            na::Rotation2::new(0.1),
+            &mut ChaCha8Rng::from_seed(Default::default())
+        );
+
+        let left = 0.2 * config.speed_max;
+        let right = 0.6 * config.speed_max;
+
+        assert_relative_eq!(speed, (left + right) / 2.0);
+        assert_relative_eq!(rotation.angle(), 0.1 + (right - left) / AXLE_WIDTH);
+
+        let delta = rotation * na::Vector2::new(speed, 0.0);
+
+        assert_relative_eq!(delta.x, 0.002 * 0.3_f32.cos());
+        assert_relative_eq!(delta.y, 0.002 * 0.3_f32.sin());
+    }
+
+    #[test]
+    fn differential_drive_speed_limits() {
+        let config = SimulationConfig::default();
+
+        let speed = |response: [f32; 2]| {
+            Locomotion::DifferentialDrive
+                .apply(
+                    &config,
+                    &response,
+                    0.002,
+                    na::Rotation2::new(0.0),
+                    &mut ChaCha8Rng::from_seed(Default::default())
+                )
+                .0
+        };
+
+        assert_relative_eq!(speed([0.0, 0.0]), config.speed_min);
+        assert_relative_eq!(speed([-0.1, 0.1]), config.speed_min);
+        assert_relative_eq!(speed([-0.1, -0.1]), -config.speed_min);
+        assert_relative_eq!(speed([-1.0, -1.0]), -config.speed_max);
+        assert_relative_eq!(speed([1.0, 1.0]), config.speed_max);
+    }
+
+    #[test]
+    fn discrete() {
+        let config = SimulationConfig::default();
+
+        let (speed, rotation, _) = Locomotion::Discrete.apply(
+            &config,
+            &[0.1, 0.3, 0.9],
+            0.002,
+            na::Rotation2::new(0.5),
+            &mut ChaCha8Rng::from_seed(Default::default())
+        );
+
+        assert_relative_eq!(speed, 0.002);
+        assert_relative_eq!(rotation.angle(), 0.5 - DISCRETE_TURN);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::*;
+
+/// Runs two simulations, both seeded with `seed`, side by side for
+/// `generations` generations and panics as soon as they diverge - i.e. when
+/// any animal's position or brain weight, or any food's position, differs
+/// between them.
+pub fn assert_deterministic(config: SimulationConfig, seed: u64, generations: usize) {
+    let mut rng_a = ChaCha8Rng::seed_from_u64(seed);
+    let mut rng_b = ChaCha8Rng::seed_from_u64(seed);
+
+    let mut sim_a = Simulation::random(config.clone(), &mut rng_a);
+    let mut sim_b = Simulation::random(config, &mut rng_b);
+
+    let mut generation = 0;
+    let mut step = 0;
+
+    loop {
+        assert_worlds_eq(&sim_a.world, &sim_b.world, generation, step);
+
+        if generation == generations {
+            return;
+        }
+
+        let evolved_a = sim_a.step(&mut rng_a).is_some();
+        let evolved_b = sim_b.step(&mut rng_b).is_some();
+
+        assert_eq!(
+            evolved_a, evolved_b,
+            "generations ended at different steps (generation {}, step {})",
+            generation, step
+        );
+
+        if evolved_a {
+            generation += 1;
+            step = 0;
+        } else {
+            step += 1;
+        }
+    }
+}
+
+fn assert_worlds_eq(a: &World, b: &World, generation: usize, step: usize) {
+    let at = format!("generation {}, step {}", generation, step);
+
+    assert_eq!(a.animals.len(), b.animals.len(), "animal count differs at {}", at);
+    assert_eq!(a.foods.len(), b.foods.len(), "food count differs at {}", at);
+
+    for (idx, (a, b)) in a.animals.iter().zip(&b.animals).enumerate() {
+        assert_eq!(a.position, b.position, "animal #{} position differs at {}", idx, at);
+
+        assert!(
+            a.brain.genes().eq(b.brain.genes()),
+            "animal #{} brain differs at {}",
+            idx,
+            at
+        );
+    }
+
+    for (idx, (a, b)) in a.foods.iter().zip(&b.foods).enumerate() {
+        assert_eq!(a.position, b.position, "food #{} position differs at {}", idx, at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_config() {
+        let config = SimulationConfig {
+            world_animals: 10,
+            world_foods: 100,
+            generation_length: GenerationLength::Fixed(200),
+            vision_noise: 0.1,
+            food_spawn: FoodSpawn::Clustered {
+                clusters: 2,
+                spread: 0.1
+            },
+            ..Default::default()
+        };
+
+        assert_deterministic(config, 42, 3);
+    }
+}
@@ -4,13 +4,31 @@ use crate::*;
 pub struct World {
     crate animals: Vec<Animal>,
     crate foods: Vec<Food>,
+
+    /// Cluster centers of the current generation, for
+    /// `FoodSpawn::Clustered`
+    crate food_centers: Vec<na::Point2<f32>>,
 }
 
 impl World {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let animals = (0..40).map(|_| Animal::random(rng)).collect();
+    /// Creates a random world, drawing animals (their bodies and brains)
+    /// from `rng` and foods from `food_rng`.
+    pub fn random(
+        config: &SimulationConfig,
+        rng: &mut dyn RngCore,
+        food_rng: &mut dyn RngCore
+    ) -> Self {
+        let animals = (0..config.world_animals)
+            .map(|_| Animal::random(config, rng))
+            .collect();
+
+        let mut world = Self {
+            animals,
+            foods: Vec::new(),
+            food_centers: Vec::new()
+        };
 
-        let foods = (0..60).map(|_| Food::random(rng)).collect();
+        world.respawn_foods(config, food_rng);
 
         // ^ Our algorithm allows for animals and foods to overlap, so
         // | it's hardly ideal - but good enough for our purposes.
@@ -19,7 +37,85 @@ impl World {
         // | Poisson disk sampling:
         // |
         // | https://en.wikipedia.org/wiki/Supersampling
-        Self { animals, foods }
+        world
+    }
+
+    /// Adds `count` fresh foods (placed according to
+    /// `SimulationConfig::food_spawn`), without exceeding `max` foods in
+    /// total
+    crate fn replenish_foods(
+        &mut self,
+        config: &SimulationConfig,
+        rng: &mut dyn RngCore,
+        count: usize,
+        max: Option<usize>
+    ) {
+        let count = match max {
+            Some(max) => count.min(max.saturating_sub(self.foods.len())),
+            None => count
+        };
+
+        for _ in 0..count {
+            let position = config.food_spawn.position(config, &self.food_centers, rng);
+
+            self.foods.push(Food::at(config, position, rng));
+        }
+    }
+
+    /// Replaces all foods with a fresh set, placed according to
+    /// `SimulationConfig::food_spawn`
+    crate fn respawn_foods(&mut self, config: &SimulationConfig, rng: &mut dyn RngCore) {
+        self.food_centers = config.food_spawn.centers(rng);
+
+        self.foods = (0..config.world_foods)
+            .map(|idx| {
+                let position =
+                    config.food_spawn.initial_position(config, &self.food_centers, idx, rng);
+
+                Food::at(config, position, rng)
+            })
+            .collect();
+    }
+
+    /// Nudges animals apart until no two of them are closer than
+    /// `min_distance`; gives up after a fixed number of rounds, returning
+    /// whether all overlaps got resolved.
+    pub fn resolve_initial_overlaps(&mut self, rng: &mut dyn RngCore, min_distance: f32) -> bool {
+        const MAX_ROUNDS: usize = 100;
+
+        for _ in 0..MAX_ROUNDS {
+            let mut resolved = true;
+
+            for i in 0..self.animals.len() {
+                for j in (i + 1)..self.animals.len() {
+                    let distance = na::distance(
+                        &self.animals[i].position,
+                        &self.animals[j].position
+                    );
+
+                    if distance > min_distance {
+                        continue;
+                    }
+
+                    resolved = false;
+
+                    let direction = na::Rotation2::new(rng.gen_range(0.0..TAU))
+                        * na::Vector2::new(min_distance, 0.0);
+
+                    let animal = &mut self.animals[j];
+
+                    animal.position += direction;
+                    animal.position.x = na::wrap(animal.position.x, 0.0, 1.0);
+                    animal.position.y = na::wrap(animal.position.y, 0.0, 1.0);
+                }
+            }
+
+            if resolved {
+                return true;
+            }
+        }
+
+        false
     }
 
     pub fn animals(&self) -> &[Animal] {
@@ -29,4 +125,34 @@ impl World {
     pub fn foods(&self) -> &[Food] {
         &self.foods
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn resolve_initial_overlaps() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let config = SimulationConfig {
+            world_animals: 10,
+            ..Default::default()
+        };
+
+        let mut world = World::random(&config, &mut rng, &mut ChaCha8Rng::from_seed(Default::default()));
+
+        for animal in &mut world.animals {
+            animal.position = na::Point2::new(0.5, 0.5);
+        }
+
+        assert!(world.resolve_initial_overlaps(&mut rng, 0.05));
+
+        for (idx, a) in world.animals.iter().enumerate() {
+            for b in &world.animals[idx + 1..] {
+                assert!(na::distance(&a.position, &b.position) > 0.05);
+            }
+        }
+    }
+}